@@ -0,0 +1,18 @@
+-- Optional glossary of user identifiers: every spelling of a symbol in any
+-- language points to one canonical name, so `Тварина` and `animal` resolve
+-- to the same variable, struct or field.
+CREATE TABLE `IdentifierGlossaryTBL` (
+  `id` int(10) unsigned NOT NULL AUTO_INCREMENT,
+  `lang_name` varchar(64) NOT NULL,
+  `identifier` varchar(128) NOT NULL,
+  `canonical` varchar(128) NOT NULL,
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `lang_identifier_unique` (`lang_name`,`identifier`),
+  KEY `canonical` (`canonical`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+INSERT INTO `IdentifierGlossaryTBL` (`lang_name`, `identifier`, `canonical`) VALUES
+('EN','animal','animal'),
+('UA','Тварина','animal'),
+('EN','name','name'),
+('UA','імя','name');
@@ -0,0 +1,2 @@
+DELETE FROM `AllLexemsTBL` WHERE `std_lexem` BETWEEN 310 AND 318;
+DELETE FROM `StdLexemeTBL` WHERE `id` BETWEEN 310 AND 318;
@@ -0,0 +1,32 @@
+-- Math natives: `sqrt`, `pow`, `abs`, `floor`, `ceil`, `round`, `min`, `max`
+-- and `random`, with their English and Ukrainian names.
+INSERT INTO `StdLexemeTBL` (`id`, `name`, `lexem_type`) VALUES
+(310,'sqrt','NativeFunc'),
+(311,'pow','NativeFunc'),
+(312,'abs','NativeFunc'),
+(313,'floor','NativeFunc'),
+(314,'ceil','NativeFunc'),
+(315,'round','NativeFunc'),
+(316,'min','NativeFunc'),
+(317,'max','NativeFunc'),
+(318,'random','NativeFunc');
+
+INSERT INTO `AllLexemsTBL` (`lang_name`, `lexem`, `type_info`, `std_lexem`) VALUES
+('EN','sqrt',NULL,310),
+('EN','pow',NULL,311),
+('EN','abs',NULL,312),
+('EN','floor',NULL,313),
+('EN','ceil',NULL,314),
+('EN','round',NULL,315),
+('EN','min',NULL,316),
+('EN','max',NULL,317),
+('EN','random',NULL,318),
+('UA','корінь',NULL,310),
+('UA','степінь',NULL,311),
+('UA','модуль',NULL,312),
+('UA','округлити_вниз',NULL,313),
+('UA','округлити_вгору',NULL,314),
+('UA','округлити',NULL,315),
+('UA','мінімум',NULL,316),
+('UA','максимум',NULL,317),
+('UA','випадкове',NULL,318);
@@ -0,0 +1,2 @@
+DELETE FROM `AllLexemsTBL` WHERE `std_lexem` = 76 OR `std_lexem` BETWEEN 320 AND 328;
+DELETE FROM `StdLexemeTBL` WHERE `id` = 76 OR `id` BETWEEN 320 AND 328;
@@ -0,0 +1,36 @@
+-- String natives: `substring`, `split`, `join`, `trim`, `upper`, `lower`,
+-- `contains`, `replace` and `find`, and the `list` type keyword, with their
+-- English and Ukrainian names.
+INSERT INTO `StdLexemeTBL` (`id`, `name`, `lexem_type`) VALUES
+(76,'list','Keyword'),
+(320,'substring','NativeFunc'),
+(321,'split','NativeFunc'),
+(322,'join','NativeFunc'),
+(323,'trim','NativeFunc'),
+(324,'upper','NativeFunc'),
+(325,'lower','NativeFunc'),
+(326,'contains','NativeFunc'),
+(327,'replace','NativeFunc'),
+(328,'find','NativeFunc');
+
+INSERT INTO `AllLexemsTBL` (`lang_name`, `lexem`, `type_info`, `std_lexem`) VALUES
+('EN','list','List',76),
+('UA','список','List',76),
+('EN','substring',NULL,320),
+('EN','split',NULL,321),
+('EN','join',NULL,322),
+('EN','trim',NULL,323),
+('EN','upper',NULL,324),
+('EN','lower',NULL,325),
+('EN','contains',NULL,326),
+('EN','replace',NULL,327),
+('EN','find',NULL,328),
+('UA','підрядок',NULL,320),
+('UA','розділити',NULL,321),
+('UA','склеїти',NULL,322),
+('UA','обрізати',NULL,323),
+('UA','великі',NULL,324),
+('UA','малі',NULL,325),
+('UA','містить',NULL,326),
+('UA','замінити',NULL,327),
+('UA','знайти',NULL,328);
@@ -0,0 +1,2 @@
+DELETE FROM `AllLexemsTBL` WHERE `std_lexem` BETWEEN 340 AND 344;
+DELETE FROM `StdLexemeTBL` WHERE `id` BETWEEN 340 AND 344;
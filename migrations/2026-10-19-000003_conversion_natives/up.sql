@@ -0,0 +1,20 @@
+-- Conversion natives: `to_int`, `to_float`, `to_string`, `to_bool` and
+-- `type_of`, with their English and Ukrainian names.
+INSERT INTO `StdLexemeTBL` (`id`, `name`, `lexem_type`) VALUES
+(340,'to_int','NativeFunc'),
+(341,'to_float','NativeFunc'),
+(342,'to_string','NativeFunc'),
+(343,'to_bool','NativeFunc'),
+(344,'type_of','NativeFunc');
+
+INSERT INTO `AllLexemsTBL` (`lang_name`, `lexem`, `type_info`, `std_lexem`) VALUES
+('EN','to_int',NULL,340),
+('EN','to_float',NULL,341),
+('EN','to_string',NULL,342),
+('EN','to_bool',NULL,343),
+('EN','type_of',NULL,344),
+('UA','до_цілого',NULL,340),
+('UA','до_дійсного',NULL,341),
+('UA','до_рядка',NULL,342),
+('UA','до_булевого',NULL,343),
+('UA','тип_значення',NULL,344);
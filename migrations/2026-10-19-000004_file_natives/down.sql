@@ -0,0 +1,2 @@
+DELETE FROM `AllLexemsTBL` WHERE `std_lexem` BETWEEN 350 AND 354;
+DELETE FROM `StdLexemeTBL` WHERE `id` BETWEEN 350 AND 354;
@@ -0,0 +1,20 @@
+-- File system natives: `read_file`, `write_file`, `append_file`, `list_dir`
+-- and `exists`, with their English and Ukrainian names.
+INSERT INTO `StdLexemeTBL` (`id`, `name`, `lexem_type`) VALUES
+(350,'read_file','NativeFunc'),
+(351,'write_file','NativeFunc'),
+(352,'append_file','NativeFunc'),
+(353,'list_dir','NativeFunc'),
+(354,'exists','NativeFunc');
+
+INSERT INTO `AllLexemsTBL` (`lang_name`, `lexem`, `type_info`, `std_lexem`) VALUES
+('EN','read_file',NULL,350),
+('EN','write_file',NULL,351),
+('EN','append_file',NULL,352),
+('EN','list_dir',NULL,353),
+('EN','exists',NULL,354),
+('UA','прочитати_файл',NULL,350),
+('UA','записати_файл',NULL,351),
+('UA','дописати_файл',NULL,352),
+('UA','список_файлів',NULL,353),
+('UA','існує',NULL,354);
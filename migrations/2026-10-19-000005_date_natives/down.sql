@@ -0,0 +1,2 @@
+DELETE FROM `AllLexemsTBL` WHERE `std_lexem` BETWEEN 360 AND 369 OR `std_lexem` BETWEEN 100 AND 118;
+DELETE FROM `StdLexemeTBL` WHERE `id` BETWEEN 360 AND 369 OR `id` BETWEEN 100 AND 118;
@@ -0,0 +1,95 @@
+-- Date natives: `now`, `format_date`, `parse_date`, `date`, `days`, `hours`,
+-- `add_months`, `year`, `month` and `day`, with their English and Ukrainian
+-- names. Month (100-111) and weekday (112-118) names are `DateName` rows:
+-- `format_date` prints them in the script's language, the tokenizer keeps
+-- them ordinary identifiers.
+INSERT INTO `StdLexemeTBL` (`id`, `name`, `lexem_type`) VALUES
+(360,'now','NativeFunc'),
+(361,'format_date','NativeFunc'),
+(362,'parse_date','NativeFunc'),
+(363,'date','NativeFunc'),
+(364,'days','NativeFunc'),
+(365,'hours','NativeFunc'),
+(366,'add_months','NativeFunc'),
+(367,'year','NativeFunc'),
+(368,'month','NativeFunc'),
+(369,'day','NativeFunc'),
+(100,'January','DateName'),
+(101,'February','DateName'),
+(102,'March','DateName'),
+(103,'April','DateName'),
+(104,'May','DateName'),
+(105,'June','DateName'),
+(106,'July','DateName'),
+(107,'August','DateName'),
+(108,'September','DateName'),
+(109,'October','DateName'),
+(110,'November','DateName'),
+(111,'December','DateName'),
+(112,'Monday','DateName'),
+(113,'Tuesday','DateName'),
+(114,'Wednesday','DateName'),
+(115,'Thursday','DateName'),
+(116,'Friday','DateName'),
+(117,'Saturday','DateName'),
+(118,'Sunday','DateName');
+
+INSERT INTO `AllLexemsTBL` (`lang_name`, `lexem`, `type_info`, `std_lexem`) VALUES
+('EN','now',NULL,360),
+('EN','format_date',NULL,361),
+('EN','parse_date',NULL,362),
+('EN','date',NULL,363),
+('EN','days',NULL,364),
+('EN','hours',NULL,365),
+('EN','add_months',NULL,366),
+('EN','year',NULL,367),
+('EN','month',NULL,368),
+('EN','day',NULL,369),
+('UA','зараз',NULL,360),
+('UA','форматувати_дату',NULL,361),
+('UA','розібрати_дату',NULL,362),
+('UA','дата',NULL,363),
+('UA','дні',NULL,364),
+('UA','години',NULL,365),
+('UA','додати_місяці',NULL,366),
+('UA','рік',NULL,367),
+('UA','місяць',NULL,368),
+('UA','день',NULL,369),
+('EN','January',NULL,100),
+('EN','February',NULL,101),
+('EN','March',NULL,102),
+('EN','April',NULL,103),
+('EN','May',NULL,104),
+('EN','June',NULL,105),
+('EN','July',NULL,106),
+('EN','August',NULL,107),
+('EN','September',NULL,108),
+('EN','October',NULL,109),
+('EN','November',NULL,110),
+('EN','December',NULL,111),
+('EN','Monday',NULL,112),
+('EN','Tuesday',NULL,113),
+('EN','Wednesday',NULL,114),
+('EN','Thursday',NULL,115),
+('EN','Friday',NULL,116),
+('EN','Saturday',NULL,117),
+('EN','Sunday',NULL,118),
+('UA','січень',NULL,100),
+('UA','лютий',NULL,101),
+('UA','березень',NULL,102),
+('UA','квітень',NULL,103),
+('UA','травень',NULL,104),
+('UA','червень',NULL,105),
+('UA','липень',NULL,106),
+('UA','серпень',NULL,107),
+('UA','вересень',NULL,108),
+('UA','жовтень',NULL,109),
+('UA','листопад',NULL,110),
+('UA','грудень',NULL,111),
+('UA','понеділок',NULL,112),
+('UA','вівторок',NULL,113),
+('UA','середа',NULL,114),
+('UA','четвер',NULL,115),
+('UA','п''ятниця',NULL,116),
+('UA','субота',NULL,117),
+('UA','неділя',NULL,118);
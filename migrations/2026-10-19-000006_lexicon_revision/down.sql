@@ -0,0 +1,10 @@
+DROP TRIGGER IF EXISTS `AllLexemsTBL_insert_revision`;
+DROP TRIGGER IF EXISTS `AllLexemsTBL_update_revision`;
+DROP TRIGGER IF EXISTS `AllLexemsTBL_delete_revision`;
+DROP TRIGGER IF EXISTS `StdLexemeTBL_insert_revision`;
+DROP TRIGGER IF EXISTS `StdLexemeTBL_update_revision`;
+DROP TRIGGER IF EXISTS `StdLexemeTBL_delete_revision`;
+DROP TRIGGER IF EXISTS `IdentifierGlossaryTBL_insert_revision`;
+DROP TRIGGER IF EXISTS `IdentifierGlossaryTBL_update_revision`;
+DROP TRIGGER IF EXISTS `IdentifierGlossaryTBL_delete_revision`;
+DROP TABLE IF EXISTS `LexiconRevisionTBL`;
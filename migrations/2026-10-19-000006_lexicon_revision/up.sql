@@ -0,0 +1,31 @@
+-- Revision of the lexicon, bumped by triggers on every change of the lexeme
+-- and glossary tables: the program cache compares this one row instead of
+-- fingerprinting the whole lexicon on each run.
+CREATE TABLE `LexiconRevisionTBL` (
+  `id` tinyint(3) unsigned NOT NULL,
+  `revision` bigint(20) unsigned NOT NULL,
+  PRIMARY KEY (`id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+INSERT INTO `LexiconRevisionTBL` (`id`, `revision`) VALUES (1, 0);
+
+CREATE TRIGGER `AllLexemsTBL_insert_revision` AFTER INSERT ON `AllLexemsTBL` FOR EACH ROW
+  UPDATE `LexiconRevisionTBL` SET `revision` = `revision` + 1 WHERE `id` = 1;
+CREATE TRIGGER `AllLexemsTBL_update_revision` AFTER UPDATE ON `AllLexemsTBL` FOR EACH ROW
+  UPDATE `LexiconRevisionTBL` SET `revision` = `revision` + 1 WHERE `id` = 1;
+CREATE TRIGGER `AllLexemsTBL_delete_revision` AFTER DELETE ON `AllLexemsTBL` FOR EACH ROW
+  UPDATE `LexiconRevisionTBL` SET `revision` = `revision` + 1 WHERE `id` = 1;
+
+CREATE TRIGGER `StdLexemeTBL_insert_revision` AFTER INSERT ON `StdLexemeTBL` FOR EACH ROW
+  UPDATE `LexiconRevisionTBL` SET `revision` = `revision` + 1 WHERE `id` = 1;
+CREATE TRIGGER `StdLexemeTBL_update_revision` AFTER UPDATE ON `StdLexemeTBL` FOR EACH ROW
+  UPDATE `LexiconRevisionTBL` SET `revision` = `revision` + 1 WHERE `id` = 1;
+CREATE TRIGGER `StdLexemeTBL_delete_revision` AFTER DELETE ON `StdLexemeTBL` FOR EACH ROW
+  UPDATE `LexiconRevisionTBL` SET `revision` = `revision` + 1 WHERE `id` = 1;
+
+CREATE TRIGGER `IdentifierGlossaryTBL_insert_revision` AFTER INSERT ON `IdentifierGlossaryTBL` FOR EACH ROW
+  UPDATE `LexiconRevisionTBL` SET `revision` = `revision` + 1 WHERE `id` = 1;
+CREATE TRIGGER `IdentifierGlossaryTBL_update_revision` AFTER UPDATE ON `IdentifierGlossaryTBL` FOR EACH ROW
+  UPDATE `LexiconRevisionTBL` SET `revision` = `revision` + 1 WHERE `id` = 1;
+CREATE TRIGGER `IdentifierGlossaryTBL_delete_revision` AFTER DELETE ON `IdentifierGlossaryTBL` FOR EACH ROW
+  UPDATE `LexiconRevisionTBL` SET `revision` = `revision` + 1 WHERE `id` = 1;
@@ -0,0 +1,2 @@
+DELETE FROM `AllLexemsTBL` WHERE `std_lexem` = 370;
+DELETE FROM `StdLexemeTBL` WHERE `id` = 370;
@@ -0,0 +1,8 @@
+-- `get_env`, the native behind `--allow-env`, with its English and
+-- Ukrainian names.
+INSERT INTO `StdLexemeTBL` (`id`, `name`, `lexem_type`) VALUES
+(370,'get_env','NativeFunc');
+
+INSERT INTO `AllLexemsTBL` (`lang_name`, `lexem`, `type_info`, `std_lexem`) VALUES
+('EN','get_env',NULL,370),
+('UA','змінна_оточення',NULL,370);
@@ -1,7 +1,7 @@
 use diesel::prelude::*;
 use diesel::r2d2::{self, ConnectionManager};
 
-use crate::model::{AllLexem, StdLexeme};
+use crate::model::{AllLexem, GlossaryEntry, StdLexeme};
 
 use tracing::{*};
 
@@ -49,4 +49,89 @@ impl DB {
             }
         }
     }
+
+    /// Every lexeme row of one language.
+    pub fn select_lexems_by_lang(&self, lang: &str) -> Vec<AllLexem> {
+        let mut conn = self.pool.get().expect("Failed to get connection");
+
+        use crate::schema::AllLexemsTBL::dsl as al;
+
+        let results = al::AllLexemsTBL
+            .filter(al::lang_name.eq(lang))
+            .order(al::id)
+            .load::<AllLexem>(&mut conn);
+
+        match results {
+            Ok(rows) => {
+                info!("Lexems loaded successfully: {} ({} rows)", lang, rows.len());
+                rows
+            },
+            Err(e) => {
+                error!("Error loading lexems for '{}': {}", lang, e);
+                vec![]
+            }
+        }
+    }
+
+    /// Identifier aliases of all languages. The glossary table is optional,
+    /// so a missing table only logs an error and yields no aliases.
+    pub fn select_glossary(&self) -> Vec<GlossaryEntry> {
+        let mut conn = self.pool.get().expect("Failed to get connection");
+
+        use crate::schema::IdentifierGlossaryTBL::dsl as gl;
+
+        match gl::IdentifierGlossaryTBL.load::<GlossaryEntry>(&mut conn) {
+            Ok(rows) => {
+                info!("Glossary loaded successfully ({} rows)", rows.len());
+                rows
+            },
+            Err(e) => {
+                error!("Error loading identifier glossary: {}", e);
+                vec![]
+            }
+        }
+    }
+
+    /// Counter the lexicon tables' triggers bump on every change; `None`
+    /// when the revision table has not been migrated yet.
+    pub fn select_lexicon_revision(&self) -> Option<u64> {
+        let mut conn = self.pool.get().expect("Failed to get connection");
+
+        use crate::schema::LexiconRevisionTBL::dsl as rev;
+
+        match rev::LexiconRevisionTBL.select(rev::revision).first::<u64>(&mut conn) {
+            Ok(revision) => {
+                info!("Lexicon revision: {}", revision);
+                Some(revision)
+            },
+            Err(e) => {
+                warn!("Error loading lexicon revision: {}", e);
+                None
+            }
+        }
+    }
+
+    /// The whole lexicon joined with the standard lexemes, ordered by id.
+    pub fn select_lexicon(&self) -> Vec<(AllLexem, StdLexeme)> {
+        let mut conn = self.pool.get().expect("Failed to get connection");
+
+        use crate::schema::AllLexemsTBL::dsl as al;
+        use crate::schema::StdLexemeTBL::dsl as std;
+
+        let results = al::AllLexemsTBL
+            .inner_join(std::StdLexemeTBL.on(al::std_lexem.eq(std::id)))
+            .order(al::id)
+            .load::<(AllLexem, StdLexeme)>(&mut conn);
+
+        match results {
+            Ok(rows) => {
+                info!("Lexicon loaded successfully ({} rows)", rows.len());
+                rows
+            },
+            Err(e) => {
+                error!("Error loading lexicon: {}", e);
+                vec![]
+            }
+        }
+    }
 }
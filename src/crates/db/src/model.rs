@@ -1,8 +1,9 @@
 use diesel::prelude::*;
-use crate::schema::{AllLexemsTBL, StdLexemeTBL};
+use serde::{Deserialize, Serialize};
+use crate::schema::{AllLexemsTBL, IdentifierGlossaryTBL, StdLexemeTBL};
 
 
-#[derive(Queryable, Selectable, Debug, Clone)]
+#[derive(Queryable, Selectable, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[diesel(table_name = AllLexemsTBL)]
 pub struct AllLexem {
     pub id: u32,
@@ -18,4 +19,13 @@ pub struct StdLexeme {
     pub id: u32,
     pub name: String,
     pub lexem_type: String,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[diesel(table_name = IdentifierGlossaryTBL)]
+pub struct GlossaryEntry {
+    pub id: u32,
+    pub lang_name: String,
+    pub identifier: String,
+    pub canonical: String,
 }
\ No newline at end of file
@@ -0,0 +1,121 @@
+use std::fmt;
+use db::model::{AllLexem, StdLexeme};
+use interpreter::checker::{TypeChecker, TypeError};
+use interpreter::error::RuntimeError;
+use interpreter::interpreter::{Interpreter, RuntimeValue};
+use interpreter::natives::Native;
+use lexer::ast::{AstParser, ParseError};
+use lexer::glossary::Glossary;
+use lexer::lexicon::{Lexicon, MemoryLexicon};
+use lexer::tokenizer::{self, std_ids};
+use tracing::{info, debug};
+
+/// Std lexeme IDs given to host functions, far above the ones of the lexicon.
+pub const FIRST_HOST_ID: u32 = 100_000;
+
+/// Host function names in front of the lexicon the engine was created with.
+struct HostLexicon {
+    host: MemoryLexicon,
+    base: Box<dyn Lexicon>,
+}
+
+impl Lexicon for HostLexicon {
+    fn lookup(&self, lexem: &str) -> Vec<(AllLexem, StdLexeme)> {
+        let rows = self.host.lookup(lexem);
+        if rows.is_empty() { self.base.lookup(lexem) } else { rows }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EngineError {
+    /// The source does not parse.
+    Syntax(ParseError),
+    Type(Vec<TypeError>),
+    Runtime(RuntimeError),
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EngineError::Syntax(e) => write!(f, "{}", e),
+            EngineError::Type(errors) => {
+                let lines: Vec<String> = errors.iter().map(ToString::to_string).collect();
+                write!(f, "{}", lines.join("\n"))
+            }
+            EngineError::Runtime(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for EngineError {}
+
+/// Runs Polyglot scripts inside a host application.
+///
+/// Every `eval` is type-checked and interpreted against the state left by the
+/// previous ones: globals, functions and structs stay defined.
+pub struct Engine {
+    lexicon: HostLexicon,
+    checker: TypeChecker,
+    interpreter: Interpreter,
+    next_id: u32,
+}
+
+impl Engine {
+    pub fn new(lexicon: impl Lexicon + 'static) -> Self {
+        Self::with_glossary(lexicon, Glossary::default())
+    }
+
+    /// Engine that resolves identifier aliases through `glossary`.
+    pub fn with_glossary(lexicon: impl Lexicon + 'static, glossary: Glossary) -> Self {
+        Engine {
+            lexicon: HostLexicon { host: MemoryLexicon::default(), base: Box::new(lexicon) },
+            checker: TypeChecker::with_glossary(glossary.clone()),
+            interpreter: Interpreter::with_glossary(glossary),
+            next_id: FIRST_HOST_ID,
+        }
+    }
+
+    /// Makes `native` callable from scripts as `name`, replacing a previous
+    /// host function of that name.
+    pub fn register(&mut self, name: &str, native: Native) {
+        let id = match self.lexicon.host.lookup(name).first() {
+            Some((row, _)) => row.std_lexem,
+            None => {
+                let id = self.next_id;
+                self.next_id += 1;
+                self.lexicon.host.insert("HOST", name, id, "NativeFunc");
+                id
+            }
+        };
+        debug_assert!(std_ids::is_native(id));
+        debug!("Host function '{}' registered as {}", name, id);
+        self.checker.natives.register(id, native.clone());
+        self.interpreter.natives.register(id, native);
+    }
+
+    /// Defines an immutable global for the scripts evaluated next.
+    pub fn set_global(&mut self, name: &str, value: impl Into<RuntimeValue>) {
+        self.interpreter.set_global(name, value.into());
+    }
+
+    pub fn global(&self, name: &str) -> Option<RuntimeValue> {
+        self.interpreter.global(name)
+    }
+
+    /// The interpreter scripts run on, e.g. to set its `limits` or `permissions`.
+    pub fn interpreter_mut(&mut self) -> &mut Interpreter {
+        &mut self.interpreter
+    }
+
+    pub fn eval(&mut self, source: &str) -> Result<(), EngineError> {
+        info!("--- Engine Eval ---");
+        let tokens = tokenizer::Parser::new(&self.lexicon).parse(source);
+        let statements = AstParser::new(tokens).parse().map_err(EngineError::Syntax)?;
+        // Оголошення невдалого скрипта не повинні лишатися в чекері
+        let mut checker = self.checker.clone();
+        checker.check(&statements).map_err(EngineError::Type)?;
+        self.interpreter.interpret(statements).map_err(EngineError::Runtime)?;
+        self.checker = checker;
+        Ok(())
+    }
+}
@@ -0,0 +1 @@
+pub mod engine;
\ No newline at end of file
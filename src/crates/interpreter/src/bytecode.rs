@@ -0,0 +1,131 @@
+use std::collections::HashSet;
+use std::rc::Rc;
+use lexer::ast::DataType;
+use lexer::tokenizer::Span;
+use crate::interpreter::RuntimeValue;
+
+/// One VM instruction. Jump targets are absolute offsets in the chunk, local
+/// slots are relative to the frame base (slot 0 is the first parameter).
+///
+/// `span` is where an error raised by the instruction is reported, the same
+/// node the tree-walking `Interpreter` would blame.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Op {
+    Constant(usize),
+    /// Pushes `Program::functions[i]` as a value.
+    Function(usize),
+    Pop,
+    /// Drops every local above `slot` at the end of a block.
+    Truncate(usize),
+
+    GetLocal(usize),
+    /// Pops the top of the stack into an existing local.
+    SetLocal(usize),
+    GetGlobal { slot: usize, name: Rc<str>, span: Span },
+    DefineGlobal { slot: usize, mutable: bool, data_type: Option<DataType> },
+    SetGlobal { slot: usize, span: Span },
+    /// Reads a name the function does not declare: the innermost local of a
+    /// suspended caller called `canonical`, else the global `slot`.
+    GetDynamic { slot: usize, canonical: Rc<str>, name: Rc<str>, span: Span },
+    /// Pops the top of the stack into the variable `GetDynamic` would read.
+    SetDynamic { slot: usize, canonical: Rc<str>, span: Span },
+
+    /// Checks the top of the stack against a `let x: T` annotation.
+    CheckDeclared { name: Rc<str>, data_type: DataType, span: Span },
+    /// Checks the top of the stack against the type of a mutable local.
+    CheckAssigned { name: Rc<str>, data_type: DataType, span: Span },
+
+    Binary { op: Rc<str>, span: Span },
+    GetField { member: Rc<str>, canonical: Rc<str>, span: Span },
+    /// Pops the value, then the struct instance it is stored into.
+    SetField { member: Rc<str>, canonical: Rc<str>, span: Span },
+    DefineStruct(usize),
+    /// Pops `count` items into a new list.
+    MakeList { count: usize, span: Span },
+    /// Pops the index, then the list or string it reads from.
+    Index { span: Span },
+
+    Jump(usize),
+    /// Pops the condition, jumps when it is not truthy.
+    JumpIfFalse(usize),
+
+    /// Resolves the struct constructor or function about to be called and
+    /// checks the argument count; arguments are evaluated after it.
+    /// `struct_name` is set when the program declares a struct by that name,
+    /// which then takes precedence over `target`.
+    Callee {
+        name: Rc<str>,
+        func_id: u32,
+        struct_name: Option<Rc<str>>,
+        target: CallTarget,
+        argc: usize,
+        span: Span,
+    },
+    /// Checks argument `index` (top of the stack) against the callee's declaration.
+    CheckArg { index: usize, name: Rc<str>, span: Span },
+    /// `ctx` is the span of the caller's enclosing expression, attached to
+    /// errors coming out of the callee that have no location of their own;
+    /// `locals` are the caller's locals live at the call.
+    Call { argc: usize, name: Rc<str>, span: Span, ctx: Option<Span>, locals: Rc<[LocalName]> },
+    /// `return f(...)`: the callee reuses the running function's frame,
+    /// unless a function may read one of `locals` by name; then it is called
+    /// like `Call` and the `Return` after it returns the result.
+    TailCall { argc: usize, name: Rc<str>, span: Span, locals: Rc<[LocalName]> },
+    Return,
+
+    /// Pops `argc` arguments and calls the native registered under `id`.
+    CallNative { id: u32, argc: usize, name: Rc<str>, span: Span },
+    /// Raises an error known at compile time once execution reaches it.
+    Fail { message: Rc<str>, span: Option<Span> },
+}
+
+/// Where a called name is looked up when it is not a struct constructor.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CallTarget {
+    Local(usize),
+    Global(usize),
+    /// A function declared in an enclosing function, known at compile time.
+    Function(usize),
+    /// A name the function does not declare, looked up like `Op::GetDynamic`.
+    Dynamic { slot: usize, canonical: Rc<str> },
+}
+
+/// A local slot of a suspended caller, which callees read and assign by name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalName {
+    pub name: Rc<str>,
+    pub mutable: bool,
+    pub data_type: Option<DataType>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Chunk {
+    pub code: Vec<Op>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct CompiledFunction {
+    pub name: String,
+    pub params: Vec<(String, DataType)>,
+    pub return_type: Option<DataType>,
+    pub chunk: Rc<Chunk>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructDef {
+    pub name: String,
+    pub fields: Vec<(String, DataType)>,
+}
+
+/// Output of the `Compiler`, run by the `Vm`.
+#[derive(Debug, Default)]
+pub struct Program {
+    pub main: Rc<Chunk>,
+    pub functions: Vec<Rc<CompiledFunction>>,
+    pub constants: Vec<RuntimeValue>,
+    pub structs: Vec<Rc<StructDef>>,
+    /// Canonical names of the global slots.
+    pub globals: Vec<String>,
+    /// Names functions read from their callers' scopes.
+    pub free_names: HashSet<String>,
+}
@@ -0,0 +1,88 @@
+use lexer::ast::DataType;
+use crate::error::RuntimeError;
+use crate::interpreter::{RuntimeValue, format_value, type_label};
+use crate::natives::{Arity, Native, NativeContext, Natives};
+
+pub const TO_INT: u32 = 340;
+pub const TO_FLOAT: u32 = 341;
+pub const TO_STRING: u32 = 342;
+pub const TO_BOOL: u32 = 343;
+pub const TYPE_OF: u32 = 344;
+
+/// Registers the conversions between builtin types and `type_of`.
+pub fn register(natives: &mut Natives) {
+    natives.register(TO_INT, Native::new("to_int", Arity::Exact(1), to_int).param("value", None).returns(DataType::Int));
+    natives.register(TO_FLOAT, Native::new("to_float", Arity::Exact(1), to_float).param("value", None).returns(DataType::Float));
+    natives.register(
+        TO_STRING,
+        Native::new("to_string", Arity::Exact(1), |ctx, mut args| Ok(RuntimeValue::String(format_value(ctx.type_names, args.remove(0)))))
+            .param("value", None)
+            .returns(DataType::String),
+    );
+    natives.register(TO_BOOL, Native::new("to_bool", Arity::Exact(1), to_bool).param("value", None).returns(DataType::Bool));
+    natives.register(TYPE_OF, Native::new("type_of", Arity::Exact(1), type_of).param("value", None).returns(DataType::String));
+}
+
+fn cannot_convert(ctx: &NativeContext, value: &RuntimeValue, target: DataType) -> RuntimeError {
+    let shown = match value {
+        RuntimeValue::String(s) => format!("'{}'", s),
+        other => type_name(ctx, other),
+    };
+    RuntimeError::new(format!("Cannot convert {} to {}", shown, type_label(ctx.type_names, &target)))
+}
+
+/// Numbers are truncated towards zero; strings must hold a whole number.
+fn to_int(ctx: &mut NativeContext, args: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    match &args[0] {
+        RuntimeValue::Number(n) => Ok(RuntimeValue::Number(n.trunc())),
+        RuntimeValue::Bool(b) => Ok(RuntimeValue::Number(if *b { 1.0 } else { 0.0 })),
+        RuntimeValue::String(s) => s.trim().parse::<i64>()
+            .map(|n| RuntimeValue::Number(n as f64))
+            .map_err(|_| cannot_convert(ctx, &args[0], DataType::Int)),
+        other => Err(cannot_convert(ctx, other, DataType::Int)),
+    }
+}
+
+fn to_float(ctx: &mut NativeContext, args: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    match &args[0] {
+        RuntimeValue::Number(n) => Ok(RuntimeValue::Number(*n)),
+        RuntimeValue::Bool(b) => Ok(RuntimeValue::Number(if *b { 1.0 } else { 0.0 })),
+        // `inf` і `NaN` Rust розбирає, але скрипт не має таких значень
+        RuntimeValue::String(s) => match s.trim().parse::<f64>() {
+            Ok(n) if n.is_finite() => Ok(RuntimeValue::Number(n)),
+            _ => Err(cannot_convert(ctx, &args[0], DataType::Float)),
+        },
+        other => Err(cannot_convert(ctx, other, DataType::Float)),
+    }
+}
+
+/// Strings convert only from the way `print` shows a bool, `true` or `false`.
+fn to_bool(ctx: &mut NativeContext, args: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    match &args[0] {
+        RuntimeValue::Bool(b) => Ok(RuntimeValue::Bool(*b)),
+        RuntimeValue::Number(n) => Ok(RuntimeValue::Bool(*n != 0.0)),
+        RuntimeValue::String(s) => match s.trim() {
+            "true" => Ok(RuntimeValue::Bool(true)),
+            "false" => Ok(RuntimeValue::Bool(false)),
+            _ => Err(cannot_convert(ctx, &args[0], DataType::Bool)),
+        },
+        other => Err(cannot_convert(ctx, other, DataType::Bool)),
+    }
+}
+
+fn type_of(ctx: &mut NativeContext, args: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    Ok(RuntimeValue::String(type_name(ctx, &args[0])))
+}
+
+/// Type of a value in the script's spelling; whole numbers are `Int`.
+fn type_name(ctx: &NativeContext, value: &RuntimeValue) -> String {
+    let data_type = match value {
+        RuntimeValue::Number(n) if n.fract() == 0.0 => DataType::Int,
+        RuntimeValue::Number(_) => DataType::Float,
+        RuntimeValue::String(_) => DataType::String,
+        RuntimeValue::Bool(_) => DataType::Bool,
+        RuntimeValue::List(_) => DataType::List,
+        other => return other.type_name(),
+    };
+    type_label(ctx.type_names, &data_type)
+}
@@ -0,0 +1,450 @@
+use std::collections::HashMap;
+use std::fmt;
+use lexer::ast::{Stmt, Expr, DataType};
+use lexer::tokenizer::{Span, std_ids};
+use types::type_system::{TypeInfo, PrimitiveType, TypeCategory};
+use lexer::glossary::Glossary;
+use tracing::{info, debug};
+use crate::natives::Natives;
+use crate::error::RuntimeError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeError {
+    pub message: String,
+    pub span: Option<Span>,
+}
+
+impl fmt::Display for TypeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.span {
+            Some(span) => write!(f, "Type error at {}: {}", span, self.message),
+            None => write!(f, "Type error: {}", self.message),
+        }
+    }
+}
+
+pub fn type_info(data_type: &DataType) -> TypeInfo {
+    let (primitive, category) = match data_type {
+        DataType::Int => (PrimitiveType::Int, TypeCategory::Number),
+        DataType::Float => (PrimitiveType::Float, TypeCategory::Number),
+        DataType::String => (PrimitiveType::String, TypeCategory::Sequence),
+        DataType::Bool => (PrimitiveType::Bool, TypeCategory::Immutable),
+        DataType::List => (PrimitiveType::List, TypeCategory::Sequence),
+        DataType::Custom(_) => (PrimitiveType::Struct, TypeCategory::Mutable),
+    };
+    TypeInfo { primitive, category }
+}
+
+/// Whether a value of type `actual` can be used where `expected` is declared.
+/// Integers widen to floats; everything else must match exactly.
+pub fn is_assignable(expected: &DataType, actual: &DataType) -> bool {
+    expected == actual || (*expected == DataType::Float && *actual == DataType::Int)
+}
+
+/// What the checker knows about a variable in scope.
+#[derive(Debug, Clone)]
+struct Variable {
+    data_type: Option<DataType>,
+    /// Declared with an explicit annotation, so assignments must keep the type.
+    annotated: bool,
+    mutable: bool,
+}
+
+/// Parameters and optional return type of a declared function.
+type Signature = (Vec<(String, DataType)>, Option<DataType>);
+
+/// Static pass over the AST that runs between `AstParser` and `Interpreter`.
+///
+/// Expression types are `Option<DataType>`: `None` means the type cannot be
+/// known statically (e.g. the result of an unannotated call), and such values
+/// are never reported.
+#[derive(Clone)]
+pub struct TypeChecker {
+    scopes: Vec<HashMap<String, Variable>>,
+    /// First scope of the function being checked. Like the `Resolver`, names
+    /// declared outside it are looked up in the callers at run time, so
+    /// nothing is known about them here.
+    function_scope: usize,
+    functions: HashMap<String, Signature>,
+    structs: HashMap<String, Vec<(String, DataType)>>,
+    /// Declared return types of the functions being checked, innermost last.
+    return_types: Vec<Option<DataType>>,
+    glossary: Glossary,
+    /// Signatures of the native functions calls are checked against.
+    pub natives: Natives,
+    errors: Vec<TypeError>,
+}
+
+impl Default for TypeChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TypeChecker {
+    pub fn new() -> Self {
+        Self::with_glossary(Glossary::default())
+    }
+
+    /// Checker that treats glossary aliases (`Тварина`, `animal`) as one symbol.
+    pub fn with_glossary(glossary: Glossary) -> Self {
+        TypeChecker {
+            scopes: vec![HashMap::new()],
+            function_scope: 0,
+            functions: HashMap::new(),
+            structs: HashMap::new(),
+            return_types: Vec::new(),
+            glossary,
+            natives: Natives::standard(),
+            errors: Vec::new(),
+        }
+    }
+
+    fn canonical(&self, name: &str) -> String {
+        self.glossary.canonical(name).to_string()
+    }
+
+    fn canonical_type(&self, data_type: &DataType) -> DataType {
+        match data_type {
+            DataType::Custom(name) => DataType::Custom(self.canonical(name)),
+            other => other.clone(),
+        }
+    }
+
+    fn canonical_fields(&self, fields: &[(String, DataType)]) -> Vec<(String, DataType)> {
+        fields.iter().map(|(name, t)| (self.canonical(name), self.canonical_type(t))).collect()
+    }
+
+    /// Globals, functions and structs declared by `statements` stay known to
+    /// later calls, as they stay defined in an engine that runs them.
+    pub fn check(&mut self, statements: &[Stmt]) -> Result<(), Vec<TypeError>> {
+        info!("--- Type Checker Started ---");
+        self.collect_declarations(statements);
+        for stmt in statements {
+            self.check_stmt(stmt);
+        }
+        info!("--- Type Checker Finished ({} errors) ---", self.errors.len());
+        let errors = std::mem::take(&mut self.errors);
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+
+    // Функції та структури видимі до свого оголошення (виклики з тіл інших функцій)
+    fn collect_declarations(&mut self, statements: &[Stmt]) {
+        for stmt in statements {
+            match stmt {
+                Stmt::FunctionDeclaration { name, params, return_type, body, .. } => {
+                    let signature = (self.canonical_fields(params), return_type.as_ref().map(|t| self.canonical_type(t)));
+                    self.functions.insert(self.canonical(name), signature);
+                    self.collect_declarations(body);
+                },
+                Stmt::StructDeclaration { name, fields, .. } => {
+                    self.structs.insert(self.canonical(name), self.canonical_fields(fields));
+                },
+                Stmt::If { then_branch, else_branch, .. } => {
+                    self.collect_declarations(then_branch);
+                    if let Some(else_stmts) = else_branch {
+                        self.collect_declarations(else_stmts);
+                    }
+                },
+                Stmt::While { body, .. } => self.collect_declarations(body),
+                _ => {},
+            }
+        }
+    }
+
+    fn error(&mut self, message: String, span: Option<Span>) {
+        debug!("Type error: {}", message);
+        self.errors.push(TypeError { message, span });
+    }
+
+    fn define(&mut self, name: &str, variable: Variable) {
+        let name = self.canonical(name);
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name, variable);
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Option<&Variable> {
+        let name = self.glossary.canonical(name);
+        self.scopes[self.function_scope..].iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    fn lookup_mut(&mut self, name: &str) -> Option<&mut Variable> {
+        let name = self.canonical(name);
+        self.scopes[self.function_scope..].iter_mut().rev().find_map(|scope| scope.get_mut(&name))
+    }
+
+    fn check_block(&mut self, statements: &[Stmt], bindings: &[(String, DataType)]) {
+        self.scopes.push(HashMap::new());
+        for (name, data_type) in bindings {
+            let data_type = Some(self.canonical_type(data_type));
+            self.define(name, Variable { data_type, annotated: true, mutable: false });
+        }
+        for stmt in statements {
+            self.check_stmt(stmt);
+        }
+        self.scopes.pop();
+    }
+
+    fn check_stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::VariableDeclaration { name, mutable, data_type, value, span } => {
+                let data_type = data_type.as_ref().map(|t| self.canonical_type(t));
+                let value_type = match value {
+                    Some(value) => self.check_expr(value),
+                    None => Some(data_type.clone().unwrap_or(DataType::Int)),
+                };
+                if let Some(expected) = &data_type {
+                    self.check_type_exists(expected, *span);
+                    if let Some(actual) = &value_type
+                        && !is_assignable(expected, actual)
+                    {
+                        self.error(format!("Variable '{}' is declared as {}, got {}", name, expected, actual), Some(*span));
+                    }
+                }
+                let variable = Variable {
+                    annotated: data_type.is_some(),
+                    data_type: data_type.or(value_type),
+                    mutable: *mutable,
+                };
+                self.define(name, variable);
+            },
+            Stmt::Assignment { name, value, span } => {
+                let value_type = self.check_expr(value);
+                if let Some(variable) = self.lookup(name).cloned() {
+                    if !variable.mutable {
+                        self.error(RuntimeError::immutable(name).message, Some(*span));
+                    } else if variable.annotated
+                        && let (Some(expected), Some(actual)) = (&variable.data_type, &value_type)
+                        && !is_assignable(expected, actual)
+                    {
+                        self.error(format!("Variable '{}' is declared as {}, got {}", name, expected, actual), Some(*span));
+                    } else if !variable.annotated
+                        && variable.data_type != value_type
+                        && let Some(variable) = self.lookup_mut(name)
+                    {
+                        // Неанотована змінна могла змінити тип, далі вважаємо його невідомим
+                        variable.data_type = None;
+                    }
+                }
+            },
+            Stmt::FieldAssignment { object, member, value, span } => {
+                let field = self.member_type(object, member, *span);
+                let value_type = self.check_expr(value);
+                if let (Some((type_name, expected)), Some(actual)) = (field, value_type)
+                    && !is_assignable(&expected, &actual)
+                {
+                    self.error(format!("Field '{}' of '{}' expects {}, got {}", member, type_name, expected, actual), Some(*span));
+                }
+            },
+            Stmt::FunctionDeclaration { params, return_type, body, span, .. } => {
+                for (_, param_type) in params {
+                    self.check_type_exists(param_type, *span);
+                }
+                if let Some(return_type) = return_type {
+                    self.check_type_exists(return_type, *span);
+                }
+                self.return_types.push(return_type.as_ref().map(|t| self.canonical_type(t)));
+                let enclosing = std::mem::replace(&mut self.function_scope, self.scopes.len());
+                self.check_block(body, params);
+                self.function_scope = enclosing;
+                self.return_types.pop();
+            },
+            Stmt::StructDeclaration { fields, span, .. } => {
+                for (_, field_type) in fields {
+                    self.check_type_exists(field_type, *span);
+                }
+            },
+            Stmt::Expression(expr) => {
+                self.check_expr(expr);
+            },
+            Stmt::If { condition, then_branch, else_branch } => {
+                self.check_expr(condition);
+                self.check_block(then_branch, &[]);
+                if let Some(else_stmts) = else_branch {
+                    self.check_block(else_stmts, &[]);
+                }
+            },
+            Stmt::While { condition, body } => {
+                self.check_expr(condition);
+                self.check_block(body, &[]);
+            },
+            Stmt::Return { value, span } => {
+                let value_type = value.as_ref().and_then(|expr| self.check_expr(expr));
+                if let Some(Some(expected)) = self.return_types.last().cloned()
+                    && let Some(actual) = value_type
+                    && !is_assignable(&expected, &actual)
+                {
+                    self.error(format!("Expected return type {}, got {}", expected, actual), Some(*span));
+                }
+            },
+        }
+    }
+
+    fn check_type_exists(&mut self, data_type: &DataType, span: Span) {
+        if let DataType::Custom(name) = data_type && !self.structs.contains_key(self.glossary.canonical(name)) {
+            self.error(format!("Unknown type '{}'", name), Some(span));
+        }
+    }
+
+    fn check_expr(&mut self, expr: &Expr) -> Option<DataType> {
+        match expr {
+            Expr::Number(n) => Some(if n.fract() == 0.0 { DataType::Int } else { DataType::Float }),
+            Expr::StringLiteral(_) => Some(DataType::String),
+            Expr::Identifier { name, .. } => self.lookup(name).and_then(|variable| variable.data_type.clone()),
+            Expr::BinaryOp { left, op, right, span } => {
+                let l = self.check_expr(left);
+                let r = self.check_expr(right);
+                match (l, r) {
+                    (Some(l), Some(r)) => self.check_binary_op(&l, op, &r, *span),
+                    _ => Self::binary_result_type(op),
+                }
+            },
+            Expr::Call { func_id, func_name, args, span } => {
+                self.check_call(*func_id, func_name, args, *span)
+            },
+            Expr::MemberAccess { object, member, span } => {
+                self.member_type(object, member, *span).map(|(_, field_type)| field_type)
+            },
+            Expr::List { items, .. } => {
+                for item in items {
+                    self.check_expr(item);
+                }
+                Some(DataType::List)
+            },
+            Expr::Index { object, index, span } => {
+                let object_type = self.check_expr(object);
+                if let Some(index_type) = self.check_expr(index) && index_type != DataType::Int {
+                    self.error(format!("Index must be Int, got {}", index_type), Some(*span));
+                }
+                match object_type {
+                    Some(DataType::String) => Some(DataType::String),
+                    // Тип елементів списку не відстежується
+                    Some(DataType::List) | None => None,
+                    Some(other) => {
+                        self.error(format!("Cannot index value of type {}", other), Some(*span));
+                        None
+                    },
+                }
+            },
+        }
+    }
+
+    /// Struct name and declared type of `object.member`, when known.
+    fn member_type(&mut self, object: &Expr, member: &str, span: Span) -> Option<(String, DataType)> {
+        match self.check_expr(object)? {
+            DataType::Custom(type_name) => {
+                let field = self.structs.get(&type_name)?
+                    .iter()
+                    .find(|(field_name, _)| field_name == self.glossary.canonical(member))
+                    .map(|(_, field_type)| field_type.clone());
+                if field.is_none() {
+                    self.error(format!("Struct '{}' has no field '{}'", type_name, member), Some(span));
+                }
+                field.map(|field_type| (type_name, field_type))
+            },
+            other => {
+                self.error(format!("Cannot access member '{}' of non-struct type {}", member, other), Some(span));
+                None
+            },
+        }
+    }
+
+    /// Result type of an operator when operand types are not fully known.
+    fn binary_result_type(op: &str) -> Option<DataType> {
+        match op {
+            "<" | ">" | "<=" | ">=" | "==" | "!=" => Some(DataType::Bool),
+            _ => None,
+        }
+    }
+
+    fn check_binary_op(&mut self, left: &DataType, op: &str, right: &DataType, span: Span) -> Option<DataType> {
+        let l = type_info(left).category;
+        let r = type_info(right).category;
+        let numeric = l == TypeCategory::Number && r == TypeCategory::Number;
+        let strings = *left == DataType::String && *right == DataType::String;
+        let result = match op {
+            "+" if strings => Some(DataType::String),
+            "+" | "-" | "*" if numeric => {
+                if *left == DataType::Int && *right == DataType::Int { Some(DataType::Int) } else { Some(DataType::Float) }
+            },
+            // Частка цілих буває цілою (4 / 2), тож її тип невідомий
+            "/" if *left == DataType::Int && *right == DataType::Int => return None,
+            "/" if numeric => Some(DataType::Float),
+            "<" | ">" | "<=" | ">=" if numeric => Some(DataType::Bool),
+            "==" | "!=" if numeric || strings => Some(DataType::Bool),
+            _ => None,
+        };
+        if result.is_none() {
+            self.error(format!("Invalid operands for operator '{}': {} and {}", op, left, right), Some(span));
+            return Self::binary_result_type(op);
+        }
+        result
+    }
+
+    fn check_args(&mut self, callee: &str, params: &[(String, DataType)], args: &[Expr], span: Span) {
+        let arg_types: Vec<Option<DataType>> = args.iter().map(|arg| self.check_expr(arg)).collect();
+        if params.len() != args.len() {
+            self.error(format!("'{}' expects {} arguments, got {}", callee, params.len(), args.len()), Some(span));
+            return;
+        }
+        for ((param_name, param_type), arg_type) in params.iter().zip(arg_types) {
+            if let Some(arg_type) = arg_type && !is_assignable(param_type, &arg_type) {
+                self.error(format!(
+                    "Argument '{}' of '{}' expects {}, got {}",
+                    param_name, callee, param_type, arg_type
+                ), Some(span));
+            }
+        }
+    }
+
+    fn check_native(&mut self, func_id: u32, func_name: &str, args: &[Expr], span: Span) -> Option<DataType> {
+        let arg_types: Vec<Option<DataType>> = args.iter().map(|arg| self.check_expr(arg)).collect();
+        let Some(native) = self.natives.get(func_id).cloned() else {
+            self.error(format!("Unknown native function '{}'", func_name), Some(span));
+            return None;
+        };
+        if !native.arity.accepts(args.len()) {
+            self.error(format!("'{}' expects {}, got {}", func_name, native.arity, args.len()), Some(span));
+            return native.returns;
+        }
+        for (index, arg_type) in arg_types.into_iter().enumerate() {
+            if let Some((param_name, Some(param_type))) = native.param_at(index)
+                && let Some(arg_type) = arg_type
+                && !is_assignable(param_type, &arg_type)
+            {
+                self.error(format!(
+                    "Argument '{}' of '{}' expects {}, got {}",
+                    param_name, func_name, param_type, arg_type
+                ), Some(span));
+            }
+        }
+        native.returns
+    }
+
+    fn check_call(&mut self, func_id: u32, func_name: &str, args: &[Expr], span: Span) -> Option<DataType> {
+        if std_ids::is_native(func_id) {
+            return self.check_native(func_id, func_name, args, span);
+        }
+
+        if let Some(fields) = self.structs.get(self.glossary.canonical(func_name)).cloned() {
+            self.check_args(func_name, &fields, args, span);
+            return Some(DataType::Custom(self.canonical(func_name)));
+        }
+
+        if let Some((params, return_type)) = self.functions.get(self.glossary.canonical(func_name)).cloned() {
+            self.check_args(func_name, &params, args, span);
+            return return_type;
+        }
+
+        for arg in args {
+            self.check_expr(arg);
+        }
+        // Змінну з функцією може надати й той, хто викликав
+        let name = self.glossary.canonical(func_name);
+        if !self.scopes.iter().any(|scope| scope.contains_key(name)) {
+            self.error(format!("Undefined function '{}'", func_name), Some(span));
+        }
+        None
+    }
+}
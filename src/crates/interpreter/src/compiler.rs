@@ -0,0 +1,468 @@
+use std::collections::HashSet;
+use std::rc::Rc;
+use lexer::ast::{DataType, Expr, Stmt};
+use lexer::glossary::Glossary;
+use lexer::tokenizer::{Span, std_ids};
+use tracing::{info, debug};
+use crate::bytecode::{CallTarget, Chunk, CompiledFunction, LocalName, Op, Program, StructDef};
+use crate::interpreter::RuntimeValue;
+use crate::error::RuntimeError;
+
+/// A local variable slot known at compile time.
+struct Local {
+    name: String,
+    depth: usize,
+    mutable: bool,
+    data_type: Option<DataType>,
+    /// Set for the binding a function declaration creates.
+    function: Option<usize>,
+}
+
+/// Code and locals of the function being compiled; the program's top level
+/// is the outermost one, where depth 0 declares globals.
+struct FunctionState {
+    code: Vec<Op>,
+    locals: Vec<Local>,
+    depth: usize,
+    /// Canonical name and index of this function, for local recursion.
+    own: Option<(String, usize)>,
+    /// Jumps of top-level `return`s, patched to the end of the statement.
+    top_level_returns: Vec<usize>,
+}
+
+enum Resolved {
+    Local(usize),
+    Function(usize),
+    Global(usize),
+    /// Looked up at run time among the callers' locals, then the global slot.
+    Dynamic(usize),
+}
+
+/// Compiles statements into a `Program` for the `Vm`.
+///
+/// A function sees its own locals and the functions declared around it;
+/// any other name is looked up at run time among the locals of its callers,
+/// then among the globals.
+pub struct Compiler {
+    glossary: Glossary,
+    states: Vec<FunctionState>,
+    functions: Vec<Option<CompiledFunction>>,
+    constants: Vec<RuntimeValue>,
+    structs: Vec<Rc<StructDef>>,
+    struct_names: HashSet<String>,
+    globals: Vec<String>,
+    free_names: HashSet<String>,
+}
+
+impl Compiler {
+    /// `globals` are the slots already known to the VM, so globals keep their
+    /// slot across several programs run by the same VM.
+    pub fn new(glossary: Glossary, globals: Vec<String>) -> Self {
+        Compiler {
+            glossary,
+            states: Vec::new(),
+            functions: Vec::new(),
+            constants: Vec::new(),
+            structs: Vec::new(),
+            struct_names: HashSet::new(),
+            globals,
+            free_names: HashSet::new(),
+        }
+    }
+
+    pub fn compile(mut self, statements: &[Stmt]) -> Program {
+        info!("--- Compiler Started ---");
+        self.collect_struct_names(statements);
+        self.states.push(FunctionState {
+            code: Vec::new(),
+            locals: Vec::new(),
+            depth: 0,
+            own: None,
+            top_level_returns: Vec::new(),
+        });
+
+        for stmt in statements {
+            self.statement(stmt);
+            let returns = std::mem::take(&mut self.state().top_level_returns);
+            if !returns.is_empty() {
+                let end = self.state().code.len();
+                for jump in returns {
+                    self.state().code[jump] = Op::Jump(end);
+                }
+                self.emit(Op::Truncate(0));
+            }
+        }
+
+        let main = self.states.pop().expect("main state");
+        let functions: Vec<Rc<CompiledFunction>> = self.functions.into_iter()
+            .map(|f| Rc::new(f.expect("every reserved function is compiled")))
+            .collect();
+        info!("--- Compiler Finished: {} ops, {} functions ---", main.code.len(), functions.len());
+        Program {
+            main: Rc::new(Chunk { code: main.code }),
+            functions,
+            constants: self.constants,
+            structs: self.structs,
+            globals: self.globals,
+            free_names: self.free_names,
+        }
+    }
+
+    fn collect_struct_names(&mut self, statements: &[Stmt]) {
+        for stmt in statements {
+            match stmt {
+                Stmt::StructDeclaration { name, .. } => {
+                    self.struct_names.insert(self.canonical(name));
+                }
+                Stmt::FunctionDeclaration { body, .. } | Stmt::While { body, .. } => self.collect_struct_names(body),
+                Stmt::If { then_branch, else_branch, .. } => {
+                    self.collect_struct_names(then_branch);
+                    if let Some(else_branch) = else_branch {
+                        self.collect_struct_names(else_branch);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn canonical(&self, name: &str) -> String {
+        self.glossary.canonical(name).to_string()
+    }
+
+    fn canonical_type(&self, data_type: &DataType) -> DataType {
+        match data_type {
+            DataType::Custom(name) => DataType::Custom(self.canonical(name)),
+            other => other.clone(),
+        }
+    }
+
+    fn canonical_fields(&self, fields: &[(String, DataType)]) -> Vec<(String, DataType)> {
+        fields.iter().map(|(name, t)| (self.canonical(name), self.canonical_type(t))).collect()
+    }
+
+    fn state(&mut self) -> &mut FunctionState {
+        self.states.last_mut().expect("function state")
+    }
+
+    fn emit(&mut self, op: Op) -> usize {
+        let code = &mut self.state().code;
+        code.push(op);
+        code.len() - 1
+    }
+
+    fn constant(&mut self, value: RuntimeValue) {
+        self.constants.push(value);
+        self.emit(Op::Constant(self.constants.len() - 1));
+    }
+
+    fn global_slot(&mut self, name: &str) -> usize {
+        match self.globals.iter().position(|g| g == name) {
+            Some(slot) => slot,
+            None => {
+                self.globals.push(name.to_string());
+                self.globals.len() - 1
+            }
+        }
+    }
+
+    fn resolve(&mut self, name: &str) -> Resolved {
+        let current = self.states.len() - 1;
+        if let Some(slot) = self.states[current].locals.iter().rposition(|l| l.name == name) {
+            return Resolved::Local(slot);
+        }
+        for state in self.states[..=current].iter().rev() {
+            if let Some((own, index)) = &state.own && own == name {
+                return Resolved::Function(*index);
+            }
+            if let Some(local) = state.locals.iter().rev().find(|l| l.name == name) {
+                if let Some(index) = local.function {
+                    return Resolved::Function(index);
+                }
+                // Змінну зовнішньої функції шукають серед тих, хто викликав
+                break;
+            }
+        }
+        let slot = self.global_slot(name);
+        if current == 0 {
+            return Resolved::Global(slot);
+        }
+        self.free_names.insert(name.to_string());
+        Resolved::Dynamic(slot)
+    }
+
+    /// Locals of the current function live at this point, by slot.
+    fn live_locals(&mut self) -> Rc<[LocalName]> {
+        self.state().locals.iter().map(|local| LocalName {
+            name: local.name.as_str().into(),
+            mutable: local.mutable,
+            data_type: local.data_type.clone(),
+        }).collect()
+    }
+
+    /// Binds the value on top of the stack to `name` in the current scope.
+    fn declare(&mut self, name: String, mutable: bool, data_type: Option<DataType>, function: Option<usize>) {
+        let is_global = self.states.len() == 1 && self.state().depth == 0;
+        if is_global {
+            let slot = self.global_slot(&name);
+            self.emit(Op::DefineGlobal { slot, mutable, data_type });
+            return;
+        }
+
+        let state = self.state();
+        let depth = state.depth;
+        let existing = state.locals.iter().rposition(|l| l.depth == depth && l.name == name);
+        match existing {
+            Some(slot) => {
+                let local = &mut state.locals[slot];
+                local.mutable = mutable;
+                local.data_type = data_type;
+                local.function = function;
+                self.emit(Op::SetLocal(slot));
+            }
+            None => state.locals.push(Local { name, depth, mutable, data_type, function }),
+        }
+    }
+
+    fn statement(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::VariableDeclaration { name, mutable, data_type, value, span } => {
+                let data_type = data_type.as_ref().map(|t| self.canonical_type(t));
+                match value {
+                    Some(value) => self.expr(value, Some(*span)),
+                    None => self.constant(RuntimeValue::zero(data_type.as_ref())),
+                }
+                if let Some(data_type) = &data_type {
+                    self.emit(Op::CheckDeclared { name: name.as_str().into(), data_type: data_type.clone(), span: *span });
+                }
+                self.declare(self.canonical(name), *mutable, data_type, None);
+            }
+            Stmt::Assignment { name, value, span } => {
+                self.expr(value, Some(*span));
+                let canonical = self.canonical(name);
+                match self.resolve(&canonical) {
+                    Resolved::Local(slot) => {
+                        let local = &self.state().locals[slot];
+                        if !local.mutable {
+                            self.emit(Op::Fail { message: RuntimeError::immutable(&canonical).message.into(), span: Some(*span) });
+                            return;
+                        }
+                        if let Some(data_type) = local.data_type.clone() {
+                            self.emit(Op::CheckAssigned { name: canonical.into(), data_type, span: *span });
+                        }
+                        self.emit(Op::SetLocal(slot));
+                    }
+                    Resolved::Function(_) => {
+                        self.emit(Op::Fail { message: RuntimeError::immutable(&canonical).message.into(), span: Some(*span) });
+                    }
+                    Resolved::Global(slot) => {
+                        self.emit(Op::SetGlobal { slot, span: *span });
+                    }
+                    Resolved::Dynamic(slot) => {
+                        self.emit(Op::SetDynamic { slot, canonical: canonical.into(), span: *span });
+                    }
+                }
+            }
+            Stmt::FieldAssignment { object, member, value, span } => {
+                self.expr(object, Some(*span));
+                self.expr(value, Some(*span));
+                let canonical = self.canonical(member);
+                self.emit(Op::SetField { member: member.as_str().into(), canonical: canonical.into(), span: *span });
+            }
+            Stmt::FunctionDeclaration { name, params, return_type, body, .. } => {
+                debug!("Compile function: {}", name);
+                let index = self.function(name, params, return_type, body);
+                self.emit(Op::Function(index));
+                self.declare(self.canonical(name), false, None, Some(index));
+            }
+            Stmt::StructDeclaration { name, fields, .. } => {
+                self.structs.push(Rc::new(StructDef {
+                    name: self.canonical(name),
+                    fields: self.canonical_fields(fields),
+                }));
+                self.emit(Op::DefineStruct(self.structs.len() - 1));
+            }
+            Stmt::Expression(expr) => {
+                self.expr(expr, None);
+                self.emit(Op::Pop);
+            }
+            Stmt::If { condition, then_branch, else_branch } => {
+                self.expr(condition, None);
+                let to_else = self.emit(Op::JumpIfFalse(0));
+                self.block(then_branch);
+                match else_branch {
+                    Some(else_branch) => {
+                        let to_end = self.emit(Op::Jump(0));
+                        self.patch(to_else);
+                        self.block(else_branch);
+                        self.patch(to_end);
+                    }
+                    None => self.patch(to_else),
+                }
+            }
+            Stmt::While { condition, body } => {
+                let start = self.state().code.len();
+                self.expr(condition, None);
+                let to_end = self.emit(Op::JumpIfFalse(0));
+                self.block(body);
+                self.emit(Op::Jump(start));
+                self.patch(to_end);
+            }
+            Stmt::Return { value, .. } => {
+                if self.states.len() > 1
+                    && let Some(Expr::Call { func_id, func_name, args, span }) = value
+                    && !std_ids::is_native(*func_id)
+                {
+                    self.call(*func_id, func_name, args, *span, None, true);
+                    return;
+                }
+                match value {
+                    Some(value) => self.expr(value, None),
+                    None => self.constant(RuntimeValue::Null),
+                }
+                if self.states.len() == 1 {
+                    // `return` поза функцією лише завершує поточну інструкцію верхнього рівня
+                    self.emit(Op::Pop);
+                    let jump = self.emit(Op::Jump(0));
+                    self.state().top_level_returns.push(jump);
+                } else {
+                    self.emit(Op::Return);
+                }
+            }
+        }
+    }
+
+    fn function(&mut self, name: &str, params: &[(String, DataType)], return_type: &Option<DataType>, body: &[Stmt]) -> usize {
+        self.functions.push(None);
+        let index = self.functions.len() - 1;
+        let params = self.canonical_fields(params);
+        let return_type = return_type.as_ref().map(|t| self.canonical_type(t));
+
+        // Параметри й тіло функції живуть в одній області видимості
+        let locals = params.iter().map(|(param, data_type)| Local {
+            name: param.clone(),
+            depth: 1,
+            mutable: false,
+            data_type: Some(data_type.clone()),
+            function: None,
+        }).collect();
+        self.states.push(FunctionState {
+            code: Vec::new(),
+            locals,
+            depth: 1,
+            own: Some((self.canonical(name), index)),
+            top_level_returns: Vec::new(),
+        });
+        for stmt in body {
+            self.statement(stmt);
+        }
+        self.constant(RuntimeValue::Void);
+        self.emit(Op::Return);
+
+        let state = self.states.pop().expect("function state");
+        self.functions[index] = Some(CompiledFunction {
+            name: name.to_string(),
+            params,
+            return_type,
+            chunk: Rc::new(Chunk { code: state.code }),
+        });
+        index
+    }
+
+    fn block(&mut self, statements: &[Stmt]) {
+        self.state().depth += 1;
+        for stmt in statements {
+            self.statement(stmt);
+        }
+        let state = self.state();
+        state.depth -= 1;
+        let depth = state.depth;
+        let live = state.locals.iter().take_while(|l| l.depth <= depth).count();
+        if live < state.locals.len() {
+            state.locals.truncate(live);
+            self.emit(Op::Truncate(live));
+        }
+    }
+
+    fn patch(&mut self, jump: usize) {
+        let target = self.state().code.len();
+        match &mut self.state().code[jump] {
+            Op::Jump(to) | Op::JumpIfFalse(to) => *to = target,
+            other => unreachable!("patching {:?}", other),
+        }
+    }
+
+    /// `ctx` is the innermost enclosing node with a span, where errors
+    /// without a location of their own are reported.
+    fn expr(&mut self, expr: &Expr, ctx: Option<Span>) {
+        match expr {
+            Expr::Number(n) => self.constant(RuntimeValue::Number(*n)),
+            Expr::StringLiteral(s) => self.constant(RuntimeValue::String(s.clone())),
+            Expr::Identifier { name, span } => {
+                let canonical = self.canonical(name);
+                let op = match self.resolve(&canonical) {
+                    Resolved::Local(slot) => Op::GetLocal(slot),
+                    Resolved::Function(index) => Op::Function(index),
+                    Resolved::Global(slot) => Op::GetGlobal { slot, name: name.as_str().into(), span: *span },
+                    Resolved::Dynamic(slot) => Op::GetDynamic { slot, canonical: canonical.into(), name: name.as_str().into(), span: *span },
+                };
+                self.emit(op);
+            }
+            Expr::BinaryOp { left, op, right, span } => {
+                self.expr(left, Some(*span));
+                self.expr(right, Some(*span));
+                self.emit(Op::Binary { op: op.as_str().into(), span: *span });
+            }
+            Expr::MemberAccess { object, member, span } => {
+                self.expr(object, Some(*span));
+                let canonical = self.canonical(member);
+                self.emit(Op::GetField { member: member.as_str().into(), canonical: canonical.into(), span: *span });
+            }
+            Expr::Call { func_id, func_name, args, span } => self.call(*func_id, func_name, args, *span, ctx, false),
+            Expr::List { items, span } => {
+                for item in items {
+                    self.expr(item, Some(*span));
+                }
+                self.emit(Op::MakeList { count: items.len(), span: *span });
+            }
+            Expr::Index { object, index, span } => {
+                self.expr(object, Some(*span));
+                self.expr(index, Some(*span));
+                self.emit(Op::Index { span: *span });
+            }
+        }
+    }
+
+    /// A `tail` call replaces the running function's frame and returns its result.
+    fn call(&mut self, func_id: u32, func_name: &str, args: &[Expr], span: Span, ctx: Option<Span>, tail: bool) {
+        if std_ids::is_native(func_id) {
+            for arg in args {
+                self.expr(arg, Some(span));
+            }
+            self.emit(Op::CallNative { id: func_id, argc: args.len(), name: func_name.into(), span });
+            return;
+        }
+
+        let canonical = self.canonical(func_name);
+        let struct_name = self.struct_names.contains(&canonical).then(|| canonical.as_str().into());
+        let target = match self.resolve(&canonical) {
+            Resolved::Local(slot) => CallTarget::Local(slot),
+            Resolved::Function(index) => CallTarget::Function(index),
+            Resolved::Global(slot) => CallTarget::Global(slot),
+            Resolved::Dynamic(slot) => CallTarget::Dynamic { slot, canonical: canonical.as_str().into() },
+        };
+        let name: Rc<str> = func_name.into();
+        self.emit(Op::Callee { name: name.clone(), func_id, struct_name, target, argc: args.len(), span });
+        for (index, arg) in args.iter().enumerate() {
+            self.expr(arg, Some(span));
+            self.emit(Op::CheckArg { index, name: name.clone(), span });
+        }
+        let locals = self.live_locals();
+        if tail {
+            self.emit(Op::TailCall { argc: args.len(), name, span, locals });
+            self.emit(Op::Return);
+        } else {
+            self.emit(Op::Call { argc: args.len(), name, span, ctx, locals });
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io::{self, BufRead, Write};
+use std::rc::Rc;
+
+/// Where `print` output and `input` prompts go.
+pub trait OutputSink {
+    fn write(&mut self, text: &str) -> io::Result<()>;
+
+    /// Called before `input` blocks, so its prompt is shown.
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Where `input` reads lines from; at the end of input a line is empty.
+pub trait InputSource {
+    /// The next line, without its line break.
+    fn read_line(&mut self) -> io::Result<String>;
+}
+
+/// The process's standard output, flushed before `input` reads and when the
+/// sink is dropped.
+#[derive(Debug, Default)]
+pub struct StdoutSink;
+
+impl OutputSink for StdoutSink {
+    fn write(&mut self, text: &str) -> io::Result<()> {
+        io::stdout().lock().write_all(text.as_bytes())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stdout().lock().flush()
+    }
+}
+
+impl Drop for StdoutSink {
+    fn drop(&mut self) {
+        let _ = io::stdout().lock().flush();
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct StdinSource;
+
+impl InputSource for StdinSource {
+    fn read_line(&mut self) -> io::Result<String> {
+        let mut buffer = String::new();
+        io::stdin().lock().read_line(&mut buffer)?;
+        Ok(buffer.trim_end_matches(['\r', '\n']).to_string())
+    }
+}
+
+/// Collects output in memory. Clones share the buffer, so one can be given
+/// to an engine and the other read afterwards.
+#[derive(Debug, Clone, Default)]
+pub struct CapturedOutput {
+    buffer: Rc<RefCell<String>>,
+}
+
+impl CapturedOutput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn contents(&self) -> String {
+        self.buffer.borrow().clone()
+    }
+}
+
+impl OutputSink for CapturedOutput {
+    fn write(&mut self, text: &str) -> io::Result<()> {
+        self.buffer.borrow_mut().push_str(text);
+        Ok(())
+    }
+}
+
+/// Answers `input` with prepared lines, then with empty ones.
+#[derive(Debug, Clone, Default)]
+pub struct ScriptedInput {
+    lines: VecDeque<String>,
+}
+
+impl ScriptedInput {
+    pub fn new<S: Into<String>>(lines: impl IntoIterator<Item = S>) -> Self {
+        ScriptedInput { lines: lines.into_iter().map(Into::into).collect() }
+    }
+}
+
+impl InputSource for ScriptedInput {
+    fn read_line(&mut self) -> io::Result<String> {
+        Ok(self.lines.pop_front().unwrap_or_default())
+    }
+}
@@ -0,0 +1,91 @@
+use crate::error::RuntimeError;
+use crate::interpreter::RuntimeValue;
+
+// Перетворення між значеннями скрипта та типами Rust для коду, що вбудовує інтерпретатор
+
+impl From<f64> for RuntimeValue {
+    fn from(value: f64) -> Self {
+        RuntimeValue::Number(value)
+    }
+}
+
+impl From<i64> for RuntimeValue {
+    fn from(value: i64) -> Self {
+        RuntimeValue::Number(value as f64)
+    }
+}
+
+impl From<bool> for RuntimeValue {
+    fn from(value: bool) -> Self {
+        RuntimeValue::Bool(value)
+    }
+}
+
+impl From<String> for RuntimeValue {
+    fn from(value: String) -> Self {
+        RuntimeValue::String(value)
+    }
+}
+
+impl From<&str> for RuntimeValue {
+    fn from(value: &str) -> Self {
+        RuntimeValue::String(value.to_string())
+    }
+}
+
+/// `None` becomes the script's `Null`.
+impl<T: Into<RuntimeValue>> From<Option<T>> for RuntimeValue {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(RuntimeValue::Null, Into::into)
+    }
+}
+
+fn expected(type_name: &str, value: &RuntimeValue) -> RuntimeError {
+    RuntimeError::new(format!("Expected {}, got {}", type_name, value.type_name()))
+}
+
+impl TryFrom<RuntimeValue> for f64 {
+    type Error = RuntimeError;
+
+    fn try_from(value: RuntimeValue) -> Result<Self, Self::Error> {
+        match value {
+            RuntimeValue::Number(n) => Ok(n),
+            other => Err(expected("Number", &other)),
+        }
+    }
+}
+
+/// Only whole numbers convert; `2.5` is an error rather than truncated.
+impl TryFrom<RuntimeValue> for i64 {
+    type Error = RuntimeError;
+
+    fn try_from(value: RuntimeValue) -> Result<Self, Self::Error> {
+        match value {
+            RuntimeValue::Number(n) if n.fract() == 0.0 => Ok(n as i64),
+            RuntimeValue::Number(n) => Err(RuntimeError::new(format!("Expected Int, got {}", n))),
+            other => Err(expected("Int", &other)),
+        }
+    }
+}
+
+impl TryFrom<RuntimeValue> for bool {
+    type Error = RuntimeError;
+
+    fn try_from(value: RuntimeValue) -> Result<Self, Self::Error> {
+        match value {
+            RuntimeValue::Bool(b) => Ok(b),
+            other => Err(expected("Bool", &other)),
+        }
+    }
+}
+
+impl TryFrom<RuntimeValue> for String {
+    type Error = RuntimeError;
+
+    fn try_from(value: RuntimeValue) -> Result<Self, Self::Error> {
+        match value {
+            RuntimeValue::String(s) => Ok(s),
+            other => Err(expected("String", &other)),
+        }
+    }
+}
@@ -0,0 +1,234 @@
+use std::cmp::Reverse;
+use std::fmt::Write;
+use chrono::{DateTime, Datelike, Months, NaiveDate, NaiveDateTime, Utc};
+use lexer::ast::DataType;
+use lexer::tokenizer::std_ids;
+use unicode_segmentation::UnicodeSegmentation;
+use crate::error::RuntimeError;
+use crate::interpreter::RuntimeValue;
+use crate::natives::{Arity, Native, NativeContext, Natives, text};
+
+pub const NOW: u32 = 360;
+pub const FORMAT_DATE: u32 = 361;
+pub const PARSE_DATE: u32 = 362;
+pub const DATE: u32 = 363;
+pub const DAYS: u32 = 364;
+pub const HOURS: u32 = 365;
+pub const ADD_MONTHS: u32 = 366;
+pub const YEAR: u32 = 367;
+pub const MONTH: u32 = 368;
+pub const DAY: u32 = 369;
+
+const MONTHS: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June",
+    "July", "August", "September", "October", "November", "December",
+];
+const WEEKDAYS: [&str; 7] = ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"];
+
+/// Month and weekday names `format_date` writes for `%B` and `%A` (and
+/// their first three characters for `%b` and `%a`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct DateNames {
+    months: Vec<String>,
+    weekdays: Vec<String>,
+}
+
+impl Default for DateNames {
+    fn default() -> Self {
+        DateNames::localized(|_| None)
+    }
+}
+
+impl DateNames {
+    /// Names by their std lexeme IDs (`std_ids::JANUARY`, `std_ids::MONDAY`
+    /// and on); English for the ones `spelling` does not know.
+    pub fn localized(spelling: impl Fn(u32) -> Option<String>) -> Self {
+        let names = |first: u32, english: &[&str]| -> Vec<String> {
+            english.iter().zip(first..).map(|(name, id)| spelling(id).unwrap_or_else(|| name.to_string())).collect()
+        };
+        DateNames {
+            months: names(std_ids::JANUARY, &MONTHS),
+            weekdays: names(std_ids::MONDAY, &WEEKDAYS),
+        }
+    }
+}
+
+/// Registers the date natives. A date is a timestamp: seconds since the
+/// Unix epoch, in UTC, so dates compare and subtract as numbers and
+/// `days`/`hours` give durations to add to them.
+pub fn register(natives: &mut Natives) {
+    let number = Some(DataType::Float);
+    let int = Some(DataType::Int);
+    let string = Some(DataType::String);
+    natives.register(
+        NOW,
+        Native::new("now", Arity::Exact(0), |_, _| Ok(RuntimeValue::Number(Utc::now().timestamp_millis() as f64 / 1000.0)))
+            .returns(DataType::Float),
+    );
+    natives.register(
+        FORMAT_DATE,
+        Native::new("format_date", Arity::Exact(2), format_date)
+            .param("date", number.clone())
+            .param("format", string.clone())
+            .returns(DataType::String),
+    );
+    natives.register(
+        PARSE_DATE,
+        Native::new("parse_date", Arity::Exact(2), parse_date)
+            .param("text", string.clone())
+            .param("format", string)
+            .returns(DataType::Float),
+    );
+    natives.register(
+        DATE,
+        Native::new("date", Arity::Exact(3), date)
+            .param("year", int.clone())
+            .param("month", int.clone())
+            .param("day", int.clone())
+            .returns(DataType::Float),
+    );
+    natives.register(DAYS, Native::new("days", Arity::Exact(1), |_, args| duration(&args, 86_400.0)).param("count", number.clone()).returns(DataType::Float));
+    natives.register(HOURS, Native::new("hours", Arity::Exact(1), |_, args| duration(&args, 3_600.0)).param("count", number.clone()).returns(DataType::Float));
+    natives.register(
+        ADD_MONTHS,
+        Native::new("add_months", Arity::Exact(2), add_months)
+            .param("date", number.clone())
+            .param("count", int)
+            .returns(DataType::Float),
+    );
+    natives.register(YEAR, Native::new("year", Arity::Exact(1), |_, args| part(&args, |d| d.year())).param("date", number.clone()).returns(DataType::Int));
+    natives.register(MONTH, Native::new("month", Arity::Exact(1), |_, args| part(&args, |d| d.month() as i32)).param("date", number.clone()).returns(DataType::Int));
+    natives.register(DAY, Native::new("day", Arity::Exact(1), |_, args| part(&args, |d| d.day() as i32)).param("date", number).returns(DataType::Int));
+}
+
+fn to_datetime(value: &RuntimeValue) -> Result<DateTime<Utc>, RuntimeError> {
+    let seconds = f64::try_from(value.clone())?;
+    let millis = (seconds * 1000.0).round();
+    DateTime::from_timestamp_millis(millis as i64)
+        .filter(|_| millis.is_finite())
+        .ok_or_else(|| RuntimeError::new(format!("Timestamp {} is out of range", seconds)))
+}
+
+fn timestamp(datetime: NaiveDateTime) -> RuntimeValue {
+    RuntimeValue::Number(datetime.and_utc().timestamp_millis() as f64 / 1000.0)
+}
+
+fn abbreviated(name: &str) -> String {
+    name.graphemes(true).take(3).collect()
+}
+
+/// `chrono` strftime, with month and weekday names from `ctx.date_names`.
+fn format_date(ctx: &mut NativeContext, args: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    let datetime = to_datetime(&args[0])?;
+    let names = ctx.date_names;
+    let month = &names.months[datetime.month0() as usize];
+    let weekday = &names.weekdays[datetime.weekday().num_days_from_monday() as usize];
+
+    // Назви підставляються до chrono, бо той знає лише англійські
+    let mut format = String::new();
+    let mut chars = text(&args[1]).chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('%', Some(spec @ ('B' | 'b' | 'A' | 'a'))) => {
+                chars.next();
+                let name = match spec {
+                    'B' => month.clone(),
+                    'b' => abbreviated(month),
+                    'A' => weekday.clone(),
+                    _ => abbreviated(weekday),
+                };
+                format.push_str(&name.replace('%', "%%"));
+            }
+            ('%', Some(next)) => {
+                chars.next();
+                format.push('%');
+                format.push(next);
+            }
+            _ => format.push(c),
+        }
+    }
+
+    let mut output = String::new();
+    write!(output, "{}", datetime.format(&format))
+        .map_err(|_| RuntimeError::new(format!("Invalid date format '{}'", text(&args[1]))))?;
+    Ok(RuntimeValue::String(output))
+}
+
+/// `input` with the localized names `format` reads (`%B`, `%b`, `%A`, `%a`)
+/// replaced by the English ones chrono knows. Each specifier takes the first
+/// name after the previous one, so an abbreviation shared by a month and a
+/// weekday (`сер`) is read as the one the format expects there.
+fn english_names(input: &str, format: &str, names: &DateNames) -> String {
+    let mut output = String::new();
+    let mut rest = input;
+    let mut specs = format.chars();
+    while let Some(c) = specs.next() {
+        if c != '%' {
+            continue;
+        }
+        let spec = specs.next();
+        let (localized, english): (&[String], &[&str]) = match spec {
+            Some('B' | 'b') => (&names.months, &MONTHS),
+            Some('A' | 'a') => (&names.weekdays, &WEEKDAYS),
+            _ => continue,
+        };
+        let full = spec.is_some_and(|c| c.is_uppercase());
+        let found = localized.iter().zip(english)
+            .map(|(name, english_name)| {
+                if full { (name.clone(), english_name.to_string()) } else { (abbreviated(name), abbreviated(english_name)) }
+            })
+            .filter_map(|(name, english_name)| rest.find(&name).map(|at| (at, name, english_name)))
+            .min_by_key(|(at, name, _)| (*at, Reverse(name.len())));
+        if let Some((at, name, english_name)) = found {
+            output.push_str(&rest[..at]);
+            output.push_str(&english_name);
+            rest = &rest[at + name.len()..];
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+/// A date and time, or just a date (at midnight), in `format`; localized
+/// month and weekday names, full and abbreviated, are read as well as
+/// English ones.
+fn parse_date(ctx: &mut NativeContext, args: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    let (input, format) = (text(&args[0]), text(&args[1]));
+    let english = english_names(input, format, ctx.date_names);
+    NaiveDateTime::parse_from_str(&english, format)
+        .or_else(|_| NaiveDate::parse_from_str(&english, format).map(|d| d.and_time(Default::default())))
+        .map(timestamp)
+        .map_err(|_| RuntimeError::new(format!("Cannot parse date '{}' with format '{}'", input, format)))
+}
+
+fn date(_: &mut NativeContext, args: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    let year = i64::try_from(args[0].clone())?;
+    let month = i64::try_from(args[1].clone())?;
+    let day = i64::try_from(args[2].clone())?;
+    let date = i32::try_from(year).ok()
+        .zip(u32::try_from(month).ok())
+        .zip(u32::try_from(day).ok())
+        .and_then(|((y, m), d)| NaiveDate::from_ymd_opt(y, m, d))
+        .ok_or_else(|| RuntimeError::new(format!("Invalid date {}-{:02}-{:02}", year, month, day)))?;
+    Ok(timestamp(date.and_time(Default::default())))
+}
+
+fn duration(args: &[RuntimeValue], unit: f64) -> Result<RuntimeValue, RuntimeError> {
+    Ok(RuntimeValue::Number(f64::try_from(args[0].clone())? * unit))
+}
+
+/// Calendar months later (or earlier, for a negative count); the day is
+/// clamped to the length of the month, so Jan 31 + 1 month is Feb 28/29.
+fn add_months(_: &mut NativeContext, args: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    let datetime = to_datetime(&args[0])?.naive_utc();
+    let count = i64::try_from(args[1].clone())?;
+    let months = Months::new(count.unsigned_abs().min(u32::MAX as u64) as u32);
+    let shifted = if count >= 0 { datetime.checked_add_months(months) } else { datetime.checked_sub_months(months) };
+    shifted
+        .map(timestamp)
+        .ok_or_else(|| RuntimeError::new(format!("Date out of range after adding {} months", count)))
+}
+
+fn part(args: &[RuntimeValue], f: impl Fn(&DateTime<Utc>) -> i32) -> Result<RuntimeValue, RuntimeError> {
+    Ok(RuntimeValue::Number(f(&to_datetime(&args[0])?) as f64))
+}
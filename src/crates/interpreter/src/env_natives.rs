@@ -0,0 +1,37 @@
+use std::env;
+use lexer::ast::DataType;
+use crate::error::RuntimeError;
+use crate::interpreter::RuntimeValue;
+use crate::natives::{Arity, Native, NativeContext, Natives, text};
+use crate::permissions::Permission;
+
+pub const GET_ENV: u32 = 370;
+
+/// Registers `get_env`, which needs `Permission::Env`.
+pub fn register(natives: &mut Natives) {
+    let string = Some(DataType::String);
+    natives.register(
+        GET_ENV,
+        Native::new("get_env", Arity::Range(1, 2), get_env)
+            .param("name", string.clone())
+            .param("default", string)
+            .returns(DataType::String)
+            .needs(Permission::Env),
+    );
+}
+
+/// Value of an environment variable; `default` when it is not set, and an
+/// error if there is no default either.
+fn get_env(_: &mut NativeContext, args: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    let name = text(&args[0]);
+    match (env::var(name), args.get(1)) {
+        (Ok(value), _) => Ok(RuntimeValue::String(value)),
+        (Err(env::VarError::NotPresent), Some(default)) => Ok(RuntimeValue::String(text(default).to_string())),
+        (Err(env::VarError::NotPresent), None) => {
+            Err(RuntimeError::new(format!("Environment variable '{}' is not set", name)))
+        }
+        (Err(env::VarError::NotUnicode(_)), _) => {
+            Err(RuntimeError::new(format!("Environment variable '{}' is not valid Unicode", name)))
+        }
+    }
+}
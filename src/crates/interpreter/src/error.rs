@@ -0,0 +1,95 @@
+use std::fmt;
+use lexer::tokenizer::Span;
+
+/// One entry of the script call stack, innermost call first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StackFrame {
+    pub function: String,
+    pub call_site: Span,
+}
+
+/// Which of the `Limits` stopped a run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+    CallDepth,
+    Steps,
+    ValueSize,
+    Timeout,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuntimeError {
+    pub message: String,
+    pub span: Option<Span>,
+    pub stack: Vec<StackFrame>,
+    /// Set when the script was stopped by a sandbox limit rather than failing.
+    pub limit: Option<Limit>,
+}
+
+impl RuntimeError {
+    pub fn new(message: impl Into<String>) -> Self {
+        RuntimeError {
+            message: message.into(),
+            span: None,
+            stack: Vec::new(),
+            limit: None,
+        }
+    }
+
+    /// Assignment to a binding declared without `mut`; the checker and both
+    /// engines report it with this message.
+    pub fn immutable(name: &str) -> Self {
+        RuntimeError::new(format!("Cannot assign twice to immutable variable '{}'", name))
+    }
+
+    pub fn limit_exceeded(limit: Limit, message: impl Into<String>) -> Self {
+        RuntimeError { limit: Some(limit), ..RuntimeError::new(message) }
+    }
+
+    pub fn at(mut self, span: Span) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    /// Attaches `span` only if the error does not know its location yet,
+    /// so the innermost node that carries a span wins.
+    pub fn or_at(mut self, span: Span) -> Self {
+        if self.span.is_none() {
+            self.span = Some(span);
+        }
+        self
+    }
+
+    /// Records a function frame while the error unwinds out of a call.
+    pub fn in_function(mut self, function: &str, call_site: Span) -> Self {
+        self.stack.push(StackFrame {
+            function: function.to_string(),
+            call_site,
+        });
+        self
+    }
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.span {
+            Some(span) => write!(f, "Runtime error at {}: {}", span, self.message)?,
+            None => write!(f, "Runtime error: {}", self.message)?,
+        }
+        // Глибока рекурсія дає тисячі однакових кадрів, друкуємо кожен лише раз
+        let mut frames = self.stack.iter().peekable();
+        while let Some(frame) = frames.next() {
+            write!(f, "\n    in {} (called at {})", frame.function, frame.call_site)?;
+            let mut repeated = 0;
+            while frames.next_if_eq(&frame).is_some() {
+                repeated += 1;
+            }
+            if repeated > 0 {
+                write!(f, "\n    ... repeated {} more time(s)", repeated)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for RuntimeError {}
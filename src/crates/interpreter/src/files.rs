@@ -0,0 +1,96 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::rc::Rc;
+use lexer::ast::DataType;
+use crate::error::RuntimeError;
+use crate::interpreter::RuntimeValue;
+use crate::natives::{Arity, Native, NativeContext, Natives, text};
+use crate::permissions::Permission;
+
+pub const READ_FILE: u32 = 350;
+pub const WRITE_FILE: u32 = 351;
+pub const APPEND_FILE: u32 = 352;
+pub const LIST_DIR: u32 = 353;
+pub const EXISTS: u32 = 354;
+
+/// Registers the file system natives. Reading needs `Permission::Read`,
+/// changing files `Permission::Write`; relative paths start at the working
+/// directory of the process.
+pub fn register(natives: &mut Natives) {
+    let string = Some(DataType::String);
+    natives.register(
+        READ_FILE,
+        Native::new("read_file", Arity::Exact(1), read_file)
+            .param("path", string.clone())
+            .returns(DataType::String)
+            .needs(Permission::Read),
+    );
+    natives.register(
+        WRITE_FILE,
+        Native::new("write_file", Arity::Exact(2), |_, args| write(&args, false))
+            .param("path", string.clone())
+            .param("text", string.clone())
+            .needs(Permission::Write),
+    );
+    natives.register(
+        APPEND_FILE,
+        Native::new("append_file", Arity::Exact(2), |_, args| write(&args, true))
+            .param("path", string.clone())
+            .param("text", string.clone())
+            .needs(Permission::Write),
+    );
+    natives.register(
+        LIST_DIR,
+        Native::new("list_dir", Arity::Exact(1), list_dir)
+            .param("path", string.clone())
+            .returns(DataType::List)
+            .needs(Permission::Read),
+    );
+    natives.register(
+        EXISTS,
+        Native::new("exists", Arity::Exact(1), |_, args| Ok(RuntimeValue::Bool(path(&args[0]).exists())))
+            .param("path", string)
+            .returns(DataType::Bool)
+            .needs(Permission::Read),
+    );
+}
+
+fn path(value: &RuntimeValue) -> &Path {
+    Path::new(text(value))
+}
+
+fn failed(action: &str, path: &Path, e: io::Error) -> RuntimeError {
+    RuntimeError::new(format!("Failed to {} '{}': {}", action, path.display(), e))
+}
+
+fn read_file(_: &mut NativeContext, args: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    let path = path(&args[0]);
+    fs::read_to_string(path).map(RuntimeValue::String).map_err(|e| failed("read file", path, e))
+}
+
+/// Writes `text` to the file, creating it; `append` keeps what it held before.
+fn write(args: &[RuntimeValue], append: bool) -> Result<RuntimeValue, RuntimeError> {
+    let (path, text) = (path(&args[0]), text(&args[1]));
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(path)
+        .map_err(|e| failed("open file", path, e))?;
+    file.write_all(text.as_bytes()).map_err(|e| failed("write file", path, e))?;
+    Ok(RuntimeValue::Void)
+}
+
+/// Names of the entries of a directory, sorted.
+fn list_dir(_: &mut NativeContext, args: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    let path = path(&args[0]);
+    let mut names = Vec::new();
+    for entry in fs::read_dir(path).map_err(|e| failed("list directory", path, e))? {
+        let entry = entry.map_err(|e| failed("list directory", path, e))?;
+        names.push(entry.file_name().to_string_lossy().into_owned());
+    }
+    names.sort();
+    Ok(RuntimeValue::List(Rc::new(names.into_iter().map(RuntimeValue::String).collect())))
+}
@@ -1,372 +1,823 @@
-use std::collections::HashMap;
-use std::rc::Rc;
+use std::borrow::Cow;
 use std::cell::RefCell;
-use lexer::ast::{Stmt, Expr, DataType};
-use tracing::{info, error, debug};
-use std::io::{self, Write};
-use lexer::tokenizer::std_ids;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use unicode_segmentation::UnicodeSegmentation;
+use lexer::ast::{Stmt, DataType};
+use tracing::{info, debug};
+use lexer::tokenizer::{Span, std_ids};
+use lexer::glossary::Glossary;
+use crate::dates::DateNames;
+use crate::error::RuntimeError;
+use crate::bytecode::CompiledFunction;
+use crate::limits::{Budget, Limits};
+use crate::console::{InputSource, OutputSink, StdinSource, StdoutSink};
+use crate::natives::{NativeContext, Natives};
+use crate::permissions::Permissions;
+use crate::resolver::{Block, Call, FunctionDecl, ResolvedExpr, ResolvedStmt, Resolver, Slot};
+
+/// Fields of a struct value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Instance {
+    pub type_name: String,
+    pub fields: HashMap<String, RuntimeValue>,
+}
 
+/// Numbers, strings and bools are copied on assignment and when passed as
+/// arguments; struct instances are references, so every copy of the value
+/// sees the same fields.
 #[derive(Debug, Clone, PartialEq)]
 pub enum RuntimeValue {
     Number(f64),
     String(String),
     Bool(bool),
-    StructInstance {
-        type_name: String,
-        fields: HashMap<String, RuntimeValue>,
-    },
-    Function {
-        name: String,
-        params: Vec<(String, DataType)>,
-        body: Vec<Stmt>,
-    },
+    /// Lists cannot be changed, so copies share their items.
+    List(Rc<Vec<RuntimeValue>>),
+    StructInstance(Rc<RefCell<Instance>>),
+    Function(Rc<FunctionDecl>),
+    /// Function compiled for the bytecode VM.
+    Compiled(Rc<CompiledFunction>),
     Null,
     Void,
+}
+
+impl RuntimeValue {
+    pub fn instance(type_name: String, fields: HashMap<String, RuntimeValue>) -> Self {
+        RuntimeValue::StructInstance(Rc::new(RefCell::new(Instance { type_name, fields })))
+    }
+
+    pub fn type_name(&self) -> String {
+        match self {
+            RuntimeValue::Number(_) => "Number".to_string(),
+            RuntimeValue::String(_) => "String".to_string(),
+            RuntimeValue::Bool(_) => "Bool".to_string(),
+            RuntimeValue::List(_) => "List".to_string(),
+            RuntimeValue::StructInstance(instance) => instance.borrow().type_name.clone(),
+            RuntimeValue::Function(_) | RuntimeValue::Compiled(_) => "Function".to_string(),
+            RuntimeValue::Null => "Null".to_string(),
+            RuntimeValue::Void => "Void".to_string(),
+        }
+    }
 
-    Return(Box<RuntimeValue>),
+    /// Value of a variable declared without an initializer: the zero of its
+    /// builtin type, `0` when the type is not given.
+    pub fn zero(data_type: Option<&DataType>) -> Self {
+        match data_type {
+            None | Some(DataType::Int | DataType::Float) => RuntimeValue::Number(0.0),
+            Some(DataType::String) => RuntimeValue::String(String::new()),
+            Some(DataType::Bool) => RuntimeValue::Bool(false),
+            Some(DataType::List) => RuntimeValue::List(Rc::new(Vec::new())),
+            // Парсер вимагає ініціалізатор для змінних-структур
+            Some(DataType::Custom(_)) => RuntimeValue::Null,
+        }
+    }
+
+    /// Whether this value satisfies a declared `DataType`.
+    pub fn matches_type(&self, data_type: &DataType) -> bool {
+        match (self, data_type) {
+            (RuntimeValue::Number(n), DataType::Int) => n.fract() == 0.0,
+            (RuntimeValue::Number(_), DataType::Float) => true,
+            (RuntimeValue::String(_), DataType::String) => true,
+            (RuntimeValue::Bool(_), DataType::Bool) => true,
+            (RuntimeValue::List(_), DataType::List) => true,
+            (RuntimeValue::StructInstance(instance), DataType::Custom(name)) => instance.borrow().type_name == *name,
+            _ => false,
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
-pub struct Environment {
-    values: HashMap<String, RuntimeValue>,
-    parent: Option<Rc<RefCell<Environment>>>,
+pub(crate) fn is_truthy(val: &RuntimeValue) -> bool {
+    match val {
+        RuntimeValue::Bool(b) => *b,
+        RuntimeValue::Null => false,
+        RuntimeValue::Void => false,
+        RuntimeValue::Number(n) => *n != 0.0,
+        _ => true,
+    }
 }
 
-impl Environment {
-    pub fn new(parent: Option<Rc<RefCell<Environment>>>) -> Self {
-        Environment {
-            values: HashMap::new(),
-            parent,
+pub(crate) fn type_label(type_names: &HashMap<DataType, String>, data_type: &DataType) -> String {
+    type_names.get(data_type).cloned().unwrap_or_else(|| data_type.to_string())
+}
+
+fn format_function(
+    type_names: &HashMap<DataType, String>,
+    name: &str,
+    params: &[(String, DataType)],
+    return_type: &Option<DataType>,
+) -> String {
+    let params: Vec<String> = params.iter().map(|(_, t)| type_label(type_names, t)).collect();
+    match return_type {
+        Some(t) => format!("[Function {}({}) -> {}]", name, params.join(", "), type_label(type_names, t)),
+        None => format!("[Function {}({})]", name, params.join(", ")),
+    }
+}
+
+/// How `print` shows a value.
+pub(crate) fn format_value(type_names: &HashMap<DataType, String>, value: RuntimeValue) -> String {
+    match value {
+        RuntimeValue::Number(n) => n.to_string(),
+        RuntimeValue::String(s) => s,
+        RuntimeValue::Bool(b) => b.to_string(),
+        RuntimeValue::List(items) => {
+            let items: Vec<String> = items.iter().map(|item| format_value(type_names, item.clone())).collect();
+            format!("[{}]", items.join(", "))
+        }
+        RuntimeValue::Null => "null".to_string(),
+        RuntimeValue::Void => "void".to_string(),
+        RuntimeValue::StructInstance(instance) => format!("[Instance of {}]", instance.borrow().type_name),
+        RuntimeValue::Function(decl) => {
+            format_function(type_names, &decl.name, &decl.params, &decl.return_type)
+        }
+        RuntimeValue::Compiled(function) => {
+            format_function(type_names, &function.name, &function.params, &function.return_type)
         }
     }
+}
 
-    pub fn define(&mut self, name: String, value: RuntimeValue) {
-        self.values.insert(name, value);
+pub(crate) fn get_field(object: &RuntimeValue, member: &str, canonical: &str, span: Span) -> Result<RuntimeValue, RuntimeError> {
+    let RuntimeValue::StructInstance(instance) = object else {
+        return Err(RuntimeError::new(format!(
+            "Cannot access member '{}' of non-struct value of type {}",
+            member,
+            object.type_name()
+        )).at(span));
+    };
+    let instance = instance.borrow();
+    instance.fields.get(canonical).cloned().ok_or_else(|| {
+        RuntimeError::new(format!("Field '{}' not found in struct '{}'", member, instance.type_name)).at(span)
+    })
+}
+
+/// `object[index]`: an element of a list, or the character (grapheme) of a string.
+pub(crate) fn get_index(object: &RuntimeValue, index: &RuntimeValue, span: Span) -> Result<RuntimeValue, RuntimeError> {
+    let position = match index {
+        RuntimeValue::Number(n) if n.fract() == 0.0 => *n,
+        other => return Err(RuntimeError::new(format!("Index must be Int, got {}", other.type_name())).at(span)),
+    };
+    let (item, len) = match object {
+        RuntimeValue::List(items) => (usize::try_from(position as i64).ok().and_then(|i| items.get(i).cloned()), items.len()),
+        RuntimeValue::String(s) => {
+            let grapheme = usize::try_from(position as i64).ok().and_then(|i| s.graphemes(true).nth(i));
+            (grapheme.map(|g| RuntimeValue::String(g.to_string())), s.graphemes(true).count())
+        }
+        other => return Err(RuntimeError::new(format!("Cannot index value of type {}", other.type_name())).at(span)),
+    };
+    item.ok_or_else(|| RuntimeError::new(format!("Index {} out of range for length {}", position, len)).at(span))
+}
+
+/// `object.member = value`; `field_type` gives the declared type of the
+/// field in the struct named by its argument.
+pub(crate) fn set_field(
+    type_names: &HashMap<DataType, String>,
+    object: &RuntimeValue,
+    member: &str,
+    canonical: &str,
+    value: RuntimeValue,
+    span: Span,
+    field_type: impl FnOnce(&str) -> Option<DataType>,
+) -> Result<(), RuntimeError> {
+    let RuntimeValue::StructInstance(instance) = object else {
+        return Err(RuntimeError::new(format!(
+            "Cannot assign to member '{}' of non-struct value of type {}",
+            member,
+            object.type_name()
+        )).at(span));
+    };
+    let type_name = instance.borrow().type_name.clone();
+    if !instance.borrow().fields.contains_key(canonical) {
+        return Err(RuntimeError::new(format!("Field '{}' not found in struct '{}'", member, type_name)).at(span));
+    }
+    if let Some(expected) = field_type(&type_name)
+        && !value.matches_type(&expected)
+    {
+        return Err(RuntimeError::new(format!(
+            "Field '{}' of '{}' expects {}, got {}",
+            member, type_name, type_label(type_names, &expected), value.type_name()
+        )).at(span));
     }
+    instance.borrow_mut().fields.insert(canonical.to_string(), value);
+    Ok(())
+}
+
+pub(crate) fn binary_op(left: RuntimeValue, op: &str, right: RuntimeValue) -> Result<RuntimeValue, RuntimeError> {
+    let invalid = |l: &RuntimeValue, r: &RuntimeValue| RuntimeError::new(format!(
+        "Invalid operands for operator '{}': {} and {}",
+        op, l.type_name(), r.type_name()
+    ));
+    match (&left, &right) {
+        (RuntimeValue::Number(a), RuntimeValue::Number(b)) => match op {
+            "+" => Ok(RuntimeValue::Number(a + b)),
+            "-" => Ok(RuntimeValue::Number(a - b)),
+            "*" => Ok(RuntimeValue::Number(a * b)),
+            "/" => Ok(RuntimeValue::Number(a / b)),
+            
+            "<" => Ok(RuntimeValue::Bool(a < b)),
+            ">" => Ok(RuntimeValue::Bool(a > b)),
+            "<=" => Ok(RuntimeValue::Bool(a <= b)),
+            ">=" => Ok(RuntimeValue::Bool(a >= b)),
+            "==" => Ok(RuntimeValue::Bool(a == b)),
+            "!=" => Ok(RuntimeValue::Bool(a != b)),
+            
+            _ => Err(invalid(&left, &right)),
+        },
+        (RuntimeValue::String(a), RuntimeValue::String(b)) => {
+            if op == "+" { 
+                Ok(RuntimeValue::String(format!("{}{}", a, b)))
+            } else if op == "==" {
+                Ok(RuntimeValue::Bool(a == b))
+            } else if op == "!=" {
+                Ok(RuntimeValue::Bool(a != b))
+            } else { 
+                Err(invalid(&left, &right))
+            }
+        },
+        _ => Err(invalid(&left, &right)),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Binding {
+    value: RuntimeValue,
+    mutable: bool,
+    data_type: Option<DataType>,
+}
 
-    pub fn get(&self, name: &str) -> Option<RuntimeValue> {
-        if let Some(val) = self.values.get(name) {
-            return Some(val.clone());
+impl Binding {
+    fn constant(value: RuntimeValue) -> Self {
+        Binding { value, mutable: false, data_type: None }
+    }
+
+    fn assign(&mut self, type_names: &HashMap<DataType, String>, name: &str, value: RuntimeValue) -> Result<(), RuntimeError> {
+        if !self.mutable {
+            return Err(RuntimeError::immutable(name));
+        }
+        if let Some(data_type) = &self.data_type
+            && !value.matches_type(data_type)
+        {
+            return Err(RuntimeError::new(format!(
+                "Variable '{}' is declared as {}, got {}",
+                name, type_label(type_names, data_type), value.type_name()
+            )));
         }
-        if let Some(parent) = &self.parent {
-            return parent.borrow().get(name);
+        self.value = value;
+        Ok(())
+    }
+}
+
+/// A script function call: its declaration, call site and parameter slots.
+#[derive(Debug, Clone, PartialEq)]
+struct Frame {
+    decl: Rc<FunctionDecl>,
+    name: String,
+    span: Span,
+    scope: Vec<Binding>,
+}
+
+/// How a statement finished: on to the next one, or out of the function.
+#[derive(Debug)]
+enum Flow {
+    Next,
+    Return(RuntimeValue),
+    /// A `return f(...)` about to replace the running call.
+    TailCall(Box<Frame>),
+}
+
+/// Stack kept free below each call of a script function, and the size of a
+/// new stack segment allocated when less is left.
+const STACK_RED_ZONE: usize = 128 * 1024;
+const STACK_SEGMENT: usize = 2 * 1024 * 1024;
+
+/// Slots of a block or call defined so far, and the names the `Resolver`
+/// gave them, for callees reading them by name.
+#[derive(Debug)]
+struct Scope {
+    names: Rc<[String]>,
+    bindings: Vec<Binding>,
+}
+
+/// Local scopes of the running function (or of the top-level blocks), the
+/// innermost last; variables are addressed by the `Slot`s of the `Resolver`.
+#[derive(Debug, Default)]
+pub struct Environment {
+    scopes: Vec<Scope>,
+}
+
+impl Environment {
+    fn push(&mut self, names: Rc<[String]>, bindings: Vec<Binding>) {
+        self.scopes.push(Scope { names, bindings });
+    }
+
+    fn pop(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn scope(&self, depth: usize) -> &[Binding] {
+        &self.scopes[self.scopes.len() - 1 - depth].bindings
+    }
+
+    fn scope_mut(&mut self, depth: usize) -> &mut Vec<Binding> {
+        let len = self.scopes.len();
+        &mut self.scopes[len - 1 - depth].bindings
+    }
+
+    /// The innermost defined variable called `name`.
+    fn find(&self, name: &str) -> Option<&Binding> {
+        self.scopes.iter().rev().find_map(|scope| {
+            scope.bindings.get(scope.names.iter().position(|n| n == name)?)
+        })
+    }
+
+    fn find_mut(&mut self, name: &str) -> Option<&mut Binding> {
+        self.scopes.iter_mut().rev().find_map(|scope| {
+            scope.bindings.get_mut(scope.names.iter().position(|n| n == name)?)
+        })
+    }
+
+    /// Whether a defined variable has one of `names`.
+    fn defines_any(&self, names: &HashSet<String>) -> bool {
+        self.scopes.iter().any(|scope| scope.names[..scope.bindings.len()].iter().any(|n| names.contains(n)))
+    }
+
+    /// Defines or redefines slot `index` of the innermost scope; slots are
+    /// defined in order, so a new one always comes right after the last.
+    fn define(&mut self, index: usize, binding: Binding) {
+        let scope = self.scope_mut(0);
+        if index < scope.len() {
+            scope[index] = binding;
+        } else {
+            scope.push(binding);
         }
-        None
+    }
+
+    fn get(&self, depth: usize, index: usize) -> &RuntimeValue {
+        &self.scope(depth)[index].value
+    }
+
+    fn assign(
+        &mut self,
+        depth: usize,
+        index: usize,
+        type_names: &HashMap<DataType, String>,
+        name: &str,
+        value: RuntimeValue,
+    ) -> Result<(), RuntimeError> {
+        self.scope_mut(depth)[index].assign(type_names, name, value)
     }
 }
 
 pub struct Interpreter {
-    pub env: Rc<RefCell<Environment>>,
+    env: Environment,
+    /// Environments of the calls below the running one, the innermost last.
+    callers: Vec<Environment>,
+    /// Names functions read from their callers' scopes: a tail call keeps
+    /// the caller's frame while one of its variables has such a name.
+    free_names: HashSet<String>,
+    /// Script function calls currently running.
+    depth: usize,
+    budget: Budget,
+    pub limits: Limits,
+    pub permissions: Permissions,
+    pub natives: Natives,
+    pub output: Box<dyn OutputSink>,
+    pub input: Box<dyn InputSource>,
+    global_names: Vec<String>,
+    globals: Vec<Option<Binding>>,
     pub struct_definitions: HashMap<String, Vec<(String, DataType)>>,
+    /// Localized spellings of builtin types (`ціле`, `int`) used when printing values.
+    pub type_names: HashMap<DataType, String>,
+    /// Localized month and weekday names used by `format_date`.
+    pub date_names: DateNames,
+    glossary: Glossary,
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Interpreter {
     pub fn new() -> Self {
-        let global_env = Rc::new(RefCell::new(Environment::new(None)));
+        Self::with_glossary(Glossary::default())
+    }
+
+    /// Interpreter whose variables, functions, structs and fields resolve
+    /// identifier aliases through `glossary`.
+    pub fn with_glossary(glossary: Glossary) -> Self {
         Interpreter {
-            env: global_env,
+            env: Environment::default(),
+            callers: Vec::new(),
+            free_names: HashSet::new(),
+            depth: 0,
+            budget: Budget::start(&Limits::default()),
+            limits: Limits::default(),
+            permissions: Permissions::default(),
+            natives: Natives::standard(),
+            output: Box::new(StdoutSink),
+            input: Box::new(StdinSource),
+            global_names: Vec::new(),
+            globals: Vec::new(),
             struct_definitions: HashMap::new(),
+            type_names: HashMap::new(),
+            date_names: DateNames::default(),
+            glossary,
         }
     }
-    fn is_truthy(val: &RuntimeValue) -> bool {
-        match val {
-            RuntimeValue::Bool(b) => *b,
-            RuntimeValue::Null => false,
-            RuntimeValue::Void => false,
-            RuntimeValue::Number(n) => *n != 0.0,
-            _ => true,
-        }
+
+    fn type_label(&self, data_type: &DataType) -> String {
+        type_label(&self.type_names, data_type)
+    }
+
+    /// Value of a global variable or function, by any of its aliases.
+    pub fn global(&self, name: &str) -> Option<RuntimeValue> {
+        let name = self.glossary.canonical(name);
+        let slot = self.global_names.iter().position(|g| g == name)?;
+        self.globals.get(slot)?.as_ref().map(|g| g.value.clone())
     }
 
-    pub fn interpret(&mut self, statements: Vec<Stmt>) {
+    /// Defines an immutable global visible to the scripts interpreted next,
+    /// replacing a previous global of the same name.
+    pub fn set_global(&mut self, name: &str, value: RuntimeValue) {
+        let name = self.glossary.canonical(name).to_string();
+        let slot = match self.global_names.iter().position(|g| *g == name) {
+            Some(slot) => slot,
+            None => {
+                self.global_names.push(name);
+                self.global_names.len() - 1
+            }
+        };
+        self.globals.resize_with(self.global_names.len(), || None);
+        self.globals[slot] = Some(Binding::constant(value));
+    }
+
+    pub fn interpret(&mut self, statements: Vec<Stmt>) -> Result<(), RuntimeError> {
+        let statements = Resolver::new(&self.glossary, &mut self.global_names, &mut self.free_names).resolve(&statements);
+        self.globals.resize_with(self.global_names.len(), || None);
+        self.budget = Budget::start(&self.limits);
+
         info!("--- Interpreter Started ---");
-        for stmt in statements {
-            self.execute(stmt);
+        for stmt in &statements {
+            self.execute(stmt)?;
         }
         info!("--- Interpreter Finished ---");
+        Ok(())
+    }
+
+    fn define(&mut self, slot: &Slot, binding: Binding) {
+        match slot {
+            Slot::Local { index, .. } => self.env.define(*index, binding),
+            Slot::Global(slot) => self.globals[*slot] = Some(binding),
+            Slot::Dynamic { .. } | Slot::Function(_) => unreachable!("declarations bind a local or a global"),
+        }
     }
 
-    // fn is_truthy(&self, val: &RuntimeValue) -> bool {
-    //     match val {
-    //         RuntimeValue::Bool(b) => *b,
-    //         RuntimeValue::Null => false,
-    //         RuntimeValue::Void => false,
-    //         RuntimeValue::Number(n) => *n != 0.0, // 0 - false, остальное true
-    //         _ => true,
-    //     }
-    // }
+    /// Reads a variable without copying it out of its slot.
+    fn lookup(&self, name: &str, slot: &Slot) -> Result<Cow<'_, RuntimeValue>, RuntimeError> {
+        let undefined = || RuntimeError::new(format!("Undefined variable '{}'", name));
+        match slot {
+            Slot::Local { depth, index } => Ok(Cow::Borrowed(self.env.get(*depth, *index))),
+            Slot::Global(slot) => self.globals[*slot].as_ref()
+                .map(|g| Cow::Borrowed(&g.value))
+                .ok_or_else(undefined),
+            Slot::Dynamic { name: canonical, slot } => self.callers.iter().rev()
+                .find_map(|env| env.find(canonical))
+                .or(self.globals[*slot].as_ref())
+                .map(|g| Cow::Borrowed(&g.value))
+                .ok_or_else(undefined),
+            Slot::Function(decl) => decl.upgrade()
+                .map(|decl| Cow::Owned(RuntimeValue::Function(decl)))
+                .ok_or_else(undefined),
+        }
+    }
 
-    fn execute(&mut self, stmt: Stmt) -> RuntimeValue {
+    fn assign(&mut self, name: &str, slot: &Slot, value: RuntimeValue) -> Result<(), RuntimeError> {
+        match slot {
+            Slot::Local { depth, index } => self.env.assign(*depth, *index, &self.type_names, name, value),
+            Slot::Global(slot) => match &mut self.globals[*slot] {
+                Some(binding) => binding.assign(&self.type_names, name, value),
+                None => Err(RuntimeError::new(format!("Undefined variable '{}'", name))),
+            },
+            Slot::Dynamic { name: canonical, slot } => {
+                let binding = match self.callers.iter_mut().rev().find_map(|env| env.find_mut(canonical)) {
+                    Some(binding) => Some(binding),
+                    None => self.globals[*slot].as_mut(),
+                };
+                match binding {
+                    Some(binding) => binding.assign(&self.type_names, name, value),
+                    None => Err(RuntimeError::new(format!("Undefined variable '{}'", name))),
+                }
+            }
+            Slot::Function(_) => Err(RuntimeError::immutable(name)),
+        }
+    }
+
+    fn execute(&mut self, stmt: &ResolvedStmt) -> Result<Flow, RuntimeError> {
+        self.budget.step(&self.limits)?;
         match stmt {
-            Stmt::VariableDeclaration { name, value } => {
-                let val = self.evaluate(value);
+            ResolvedStmt::Let { name, mutable, data_type, value, slot, span } => {
+                let val = match value {
+                    Some(value) => self.evaluate(value).map_err(|e| e.or_at(*span))?,
+                    None => RuntimeValue::zero(data_type.as_ref()),
+                };
                 debug!("Var Decl: {} = {:?}", name, val);
-                self.env.borrow_mut().define(name, val);
-                RuntimeValue::Void
+                if let Some(expected) = data_type
+                    && !val.matches_type(expected)
+                {
+                    return Err(RuntimeError::new(format!(
+                        "Variable '{}' is declared as {}, got {}",
+                        name, self.type_label(expected), val.type_name()
+                    )).at(*span));
+                }
+                self.define(slot, Binding { value: val, mutable: *mutable, data_type: data_type.clone() });
+                Ok(Flow::Next)
             },
-            Stmt::FunctionDeclaration { name, params, body } => {
-                debug!("Func Decl: {}", name);
-                let func_obj = RuntimeValue::Function {
-                    name: name.clone(),
-                    params,
-                    body,
-                };
-                self.env.borrow_mut().define(name, func_obj);
-                RuntimeValue::Void
+            ResolvedStmt::Assign { name, value, slot, span } => {
+                let val = self.evaluate(value).map_err(|e| e.or_at(*span))?;
+                debug!("Assign: {} = {:?}", name, val);
+                self.assign(name, slot, val).map_err(|e| e.at(*span))?;
+                Ok(Flow::Next)
+            },
+            ResolvedStmt::SetField { object, member, canonical, value, span } => {
+                let object = self.evaluate(object).map_err(|e| e.or_at(*span))?;
+                let val = self.evaluate(value).map_err(|e| e.or_at(*span))?;
+                debug!("Set field: {} = {:?}", member, val);
+                let structs = &self.struct_definitions;
+                set_field(&self.type_names, &object, member, canonical, val, *span, |type_name| {
+                    structs.get(type_name)?.iter().find(|(field, _)| field == canonical).map(|(_, t)| t.clone())
+                })?;
+                Ok(Flow::Next)
+            },
+            ResolvedStmt::Function { decl, slot } => {
+                debug!("Func Decl: {}", decl.name);
+                self.define(slot, Binding::constant(RuntimeValue::Function(decl.clone())));
+                Ok(Flow::Next)
             },
-            Stmt::StructDeclaration { name, fields } => {
+            ResolvedStmt::Struct { name, fields } => {
                 debug!("Struct Decl: {}", name);
                 // Зберігаємо визначення структури окремо
-                self.struct_definitions.insert(name, fields);
-                RuntimeValue::Void
+                self.struct_definitions.insert(name.clone(), fields.clone());
+                Ok(Flow::Next)
             },
-            Stmt::Expression(expr) => {
-                self.evaluate(expr)
+            ResolvedStmt::Expression(expr) => {
+                self.evaluate(expr)?;
+                Ok(Flow::Next)
             },
-            Stmt::If { condition, then_branch, else_branch } => {
-                let cond_val = self.evaluate(condition);
-                // ВИПРАВЛЕНО: Self::is_truthy (без self.)
-                if Self::is_truthy(&cond_val) {
+            ResolvedStmt::If { condition, then_branch, else_branch } => {
+                let cond_val = self.evaluate(condition)?;
+                if is_truthy(&cond_val) {
                     return self.execute_block(then_branch);
-                } else if let Some(else_stmts) = else_branch {
-                    return self.execute_block(else_stmts);
+                } else if let Some(else_block) = else_branch {
+                    return self.execute_block(else_block);
                 }
-                RuntimeValue::Void
+                Ok(Flow::Next)
             },
 
-            Stmt::While { condition, body } => {
-                // ВИПРАВЛЕНО: Self::is_truthy замість self.is_truthy
-                // Тепер конфлікту немає, бо ми не позичаємо self для перевірки істини
-                while Self::is_truthy(&self.evaluate(condition.clone())) {
-                    let result = self.execute_block(body.clone());
-                    if let RuntimeValue::Return(_) = result {
-                        return result;
+            ResolvedStmt::While { condition, body } => {
+                while is_truthy(&self.evaluate(condition)?) {
+                    let flow = self.execute_block(body)?;
+                    if !matches!(flow, Flow::Next) {
+                        return Ok(flow);
                     }
                 }
-                RuntimeValue::Void
+                Ok(Flow::Next)
+            },
+            ResolvedStmt::TailCall(call) => {
+                if let Some(instance) = self.construct(call)? {
+                    return Ok(Flow::Return(instance));
+                }
+                let frame = self.prepare_call(call)?;
+                if self.env.defines_any(&self.free_names) {
+                    // Виклик може читати наші змінні, тож кадр лишається
+                    return Ok(Flow::Return(self.invoke(frame)?));
+                }
+                Ok(Flow::TailCall(Box::new(frame)))
             },
-            Stmt::Return { value } => {
+            ResolvedStmt::Return(value) => {
                 let ret_val = if let Some(expr) = value {
-                    self.evaluate(expr)
+                    self.evaluate(expr)?
                 } else {
                     RuntimeValue::Null
                 };
-                return RuntimeValue::Return(Box::new(ret_val));
+                Ok(Flow::Return(ret_val))
             },
-
-            Stmt::Expression(expr) => self.evaluate(expr),
         }
     }
 
-    fn execute_block(&mut self, statements: Vec<Stmt>) -> RuntimeValue {
-        let block_env = Rc::new(RefCell::new(Environment::new(Some(self.env.clone()))));
-        let previous_env = self.env.clone();
-        self.env = block_env;
+    fn execute_block(&mut self, block: &Block) -> Result<Flow, RuntimeError> {
+        self.env.push(block.locals.clone(), Vec::with_capacity(block.locals.len()));
 
-        let mut result = RuntimeValue::Void;
-        for stmt in statements {
+        let mut result = Ok(Flow::Next);
+        for stmt in &block.statements {
             result = self.execute(stmt);
             
-            if let RuntimeValue::Return(_) = result {
+            if !matches!(result, Ok(Flow::Next)) {
                 break;
             }
         }
 
-        self.env = previous_env; // Возвращаем старый scope
+        self.env.pop(); // Возвращаем старый scope
         result
     }
 
-    pub fn evaluate(&mut self, expr: Expr) -> RuntimeValue {
+    pub fn evaluate(&mut self, expr: &ResolvedExpr) -> Result<RuntimeValue, RuntimeError> {
+        self.budget.step(&self.limits)?;
         match expr {
-            Expr::Number(n) => RuntimeValue::Number(n),
-            Expr::StringLiteral(s) => RuntimeValue::String(s),
-            Expr::Identifier(name) => {
-                let env = self.env.borrow();
-                match env.get(&name) {
-                    Some(val) => val,
-                    None => {
-                        error!("Runtime Error: Undefined variable '{}'", name);
-                        RuntimeValue::Null
-                    }
-                }
+            ResolvedExpr::Number(n) => Ok(RuntimeValue::Number(*n)),
+            ResolvedExpr::String(s) => Ok(RuntimeValue::String(s.clone())),
+            ResolvedExpr::Variable { name, slot, span } => {
+                self.lookup(name, slot).map(Cow::into_owned).map_err(|e| e.at(*span))
             },
-            Expr::BinaryOp { left, op, right } => {
-                let l = self.evaluate(*left);
-                let r = self.evaluate(*right);
-                self.apply_binary_op(l, op, r)
+            ResolvedExpr::Binary { left, op, right, span } => {
+                let l = self.evaluate(left).map_err(|e| e.or_at(*span))?;
+                let r = self.evaluate(right).map_err(|e| e.or_at(*span))?;
+                let value = binary_op(l, op, r).map_err(|e| e.or_at(*span))?;
+                self.limits.check_size(&value).map_err(|e| e.at(*span))?;
+                Ok(value)
             },
-            Expr::Call { func_id, func_name, args } => {
-                self.call_function(func_id, func_name, args)
-            },
-            Expr::MemberAccess { object, member } => {
-                let obj_val = self.evaluate(*object);
-                if let RuntimeValue::StructInstance { fields, .. } = obj_val {
-                    if let Some(val) = fields.get(&member) {
-                        return val.clone();
-                    } else {
-                        error!("Field '{}' not found in struct instance", member);
-                        return RuntimeValue::Null;
-                    }
-                } else {
-                    error!("Cannot access member '{}' of non-struct", member);
-                    return RuntimeValue::Null;
+            ResolvedExpr::Call(call) => self.call_function(call),
+            ResolvedExpr::List { items, span } => {
+                let mut values = Vec::with_capacity(items.len());
+                for item in items {
+                    values.push(self.evaluate(item).map_err(|e| e.or_at(*span))?);
                 }
+                let list = RuntimeValue::List(Rc::new(values));
+                self.limits.check_size(&list).map_err(|e| e.at(*span))?;
+                Ok(list)
+            },
+            ResolvedExpr::Index { object, index, span } => {
+                let object = self.evaluate(object).map_err(|e| e.or_at(*span))?;
+                let index = self.evaluate(index).map_err(|e| e.or_at(*span))?;
+                get_index(&object, &index, *span)
+            },
+            ResolvedExpr::Member { object, member, canonical, span } => {
+                let obj_val = match &**object {
+                    ResolvedExpr::Variable { name, slot, span } => self.lookup(name, slot).map_err(|e| e.at(*span)),
+                    other => self.evaluate(other).map(Cow::Owned),
+                }.map_err(|e| e.or_at(*span))?;
+                get_field(&obj_val, member, canonical, *span)
             },
         }
     }
 
-    fn call_function(&mut self, func_id: u32, func_name: String, args: Vec<Expr>) -> RuntimeValue {
-        match func_id {
-            std_ids::PRINT => { // 300
-                let mut output = Vec::new();
-                for arg in args {
-                    let val = self.evaluate(arg);
-                    match val {
-                        RuntimeValue::Number(n) => output.push(n.to_string()),
-                        RuntimeValue::String(s) => output.push(s),
-                        RuntimeValue::Bool(b) => output.push(b.to_string()),
-                        RuntimeValue::Null => output.push("null".to_string()),
-                        RuntimeValue::Void => output.push("void".to_string()),
-                        RuntimeValue::StructInstance { type_name, .. } => {
-                            output.push(format!("[Instance of {}]", type_name))
-                        }
-                        RuntimeValue::Function { name, .. } => {
-                            output.push(format!("[Function {}]", name))
-                        }
-                        RuntimeValue::Return(inner_val) => {
-                            output.push(format!("{:?}", inner_val))
-                        }
-                    }
-                }
-                println!("{}", output.join(" "));
-                return RuntimeValue::Void;
-            },
-            std_ids::INPUT => { // 301
-                if let Some(arg) = args.first() {
-                    let prompt = self.evaluate(arg.clone());
-                    if let RuntimeValue::String(s) = prompt {
-                        print!("{}", s);
-                        io::stdout().flush().unwrap();
-                    }
-                }
-                let mut buffer = String::new();
-                io::stdin().read_line(&mut buffer).expect("Failed to read input");
-                return RuntimeValue::String(buffer.trim().to_string());
-            },
-            std_ids::LEN => { // 302
-                if args.len() != 1 {
-                    error!("Function 'len' expects 1 argument");
-                    return RuntimeValue::Null;
-                }
-                let val = self.evaluate(args[0].clone());
-                if let RuntimeValue::String(s) = val {
-                    return RuntimeValue::Number(s.len() as f64);
-                } else {
-                    error!("Function 'len' expects a String");
-                    return RuntimeValue::Number(0.0);
-                }
-            },
-            _ => {} // Якщо ID не нативний, йдемо далі
+    fn call_function(&mut self, call: &Call) -> Result<RuntimeValue, RuntimeError> {
+        if std_ids::is_native(call.func_id) {
+            return self.call_native(call);
         }
 
-        // 2. CONSTRUCTORS
         // Конструктори структур
-        if let Some(fields_def) = self.struct_definitions.get(&func_name).cloned() {
-            if args.len() != fields_def.len() {
-                error!("Constructor '{}' expects {} arguments, got {}", func_name, fields_def.len(), args.len());
-                return RuntimeValue::Null;
-            }
-            
-            let mut instance_fields = HashMap::new();
-            for (i, (field_name, _)) in fields_def.iter().enumerate() {
-                let val = self.evaluate(args[i].clone());
-                instance_fields.insert(field_name.clone(), val);
-            }
-            return RuntimeValue::StructInstance {
-                type_name: func_name,
-                fields: instance_fields,
-            };
+        if let Some(instance) = self.construct(call)? {
+            return Ok(instance);
         }
 
-        let func_val = {
-            let env = self.env.borrow();
-            env.get(&func_name)
+        let frame = self.prepare_call(call)?;
+        self.invoke(frame)
+    }
+
+    fn call_native(&mut self, call: &Call) -> Result<RuntimeValue, RuntimeError> {
+        let Call { func_id, name, args, span, .. } = call;
+        let mut values = Vec::with_capacity(args.len());
+        for arg in args {
+            values.push(self.evaluate(arg).map_err(|e| e.or_at(*span))?);
+        }
+        let mut ctx = NativeContext {
+            type_names: &self.type_names,
+            date_names: &self.date_names,
+            permissions: &self.permissions,
+            output: &mut *self.output,
+            input: &mut *self.input,
         };
+        let value = self.natives.call(*func_id, name, values, &mut ctx).map_err(|e| e.at(*span))?;
+        self.limits.check_size(&value).map_err(|e| e.at(*span))?;
+        Ok(value)
+    }
 
-        match func_val {
-            Some(RuntimeValue::Function { params, body, .. }) => {
-                if args.len() != params.len() {
-                    error!("Arg count mismatch for '{}'. Expected {}, got {}", func_name, params.len(), args.len());
-                    return RuntimeValue::Null;
-                }
+    /// Builds a struct instance when `call` names a declared struct.
+    fn construct(&mut self, call: &Call) -> Result<Option<RuntimeValue>, RuntimeError> {
+        let Call { name: func_name, canonical, args, span, .. } = call;
+        let span = *span;
+        // Конструктори структур
+        let Some(fields_def) = self.struct_definitions.get(canonical).cloned() else {
+            return Ok(None);
+        };
+        if args.len() != fields_def.len() {
+            return Err(RuntimeError::new(format!(
+                "Constructor '{}' expects {} arguments, got {}",
+                func_name, fields_def.len(), args.len()
+            )).at(span));
+        }
 
-                let mut evaluated_args = Vec::new();
-                for arg in args {
-                    evaluated_args.push(self.evaluate(arg));
-                }
+        let mut instance_fields = HashMap::new();
+        for (arg, (field_name, field_type)) in args.iter().zip(fields_def) {
+            let val = self.evaluate(arg).map_err(|e| e.or_at(span))?;
+            if !val.matches_type(&field_type) {
+                return Err(RuntimeError::new(format!(
+                    "Field '{}' of '{}' expects {}, got {}",
+                    field_name, func_name, self.type_label(&field_type), val.type_name()
+                )).at(span));
+            }
+            instance_fields.insert(field_name, val);
+        }
+        let instance = RuntimeValue::instance(canonical.clone(), instance_fields);
+        self.limits.check_size(&instance).map_err(|e| e.at(span))?;
+        Ok(Some(instance))
+    }
 
-                let func_env = Rc::new(RefCell::new(Environment::new(Some(self.env.clone()))));
+    /// Finds the called function and evaluates its arguments.
+    fn prepare_call(&mut self, call: &Call) -> Result<Frame, RuntimeError> {
+        let Call { func_id, name: func_name, callee, args, span, .. } = call;
+        let span = *span;
+        let decl = callee.as_ref().and_then(|slot| match self.lookup(func_name, slot).ok()?.as_ref() {
+            RuntimeValue::Function(decl) => Some(decl.clone()),
+            _ => None,
+        });
+        let Some(decl) = decl else {
+            return Err(RuntimeError::new(format!("Undefined function '{}' (ID: {})", func_name, func_id)).at(span));
+        };
 
-                for (i, (param_name, _)) in params.iter().enumerate() {
-                    func_env.borrow_mut().define(param_name.clone(), evaluated_args[i].clone());
-                }
+        if args.len() != decl.params.len() {
+            return Err(RuntimeError::new(format!(
+                "Arg count mismatch for '{}'. Expected {}, got {}",
+                func_name, decl.params.len(), args.len()
+            )).at(span));
+        }
 
-                let previous_env = self.env.clone(); // Зберігаємо старий
-                self.env = func_env;                 // Включаємо новий
+        let mut scope = Vec::with_capacity(decl.locals.len());
+        for (arg, (param_name, param_type)) in args.iter().zip(&decl.params) {
+            let val = self.evaluate(arg).map_err(|e| e.or_at(span))?;
+            if !val.matches_type(param_type) {
+                return Err(RuntimeError::new(format!(
+                    "Argument '{}' of '{}' expects {}, got {}",
+                    param_name, func_name, self.type_label(param_type), val.type_name()
+                )).at(span));
+            }
+            scope.push(Binding::constant(val));
+        }
+        Ok(Frame { decl, name: func_name.clone(), span, scope })
+    }
 
-                let mut return_value = RuntimeValue::Void; // Значення за замовчуванням
+    /// Runs a call and every tail call it makes in one Rust stack frame.
+    fn invoke(&mut self, frame: Frame) -> Result<RuntimeValue, RuntimeError> {
+        if self.depth >= self.limits.max_call_depth {
+            return Err(self.limits.stack_overflow().at(frame.span));
+        }
+        self.depth += 1;
+        // Стек Rust росте в купі, тож глибину обмежує лише `limits`
+        let result = stacker::maybe_grow(STACK_RED_ZONE, STACK_SEGMENT, || self.run_frames(frame));
+        self.depth -= 1;
+        result
+    }
 
-                for stmt in body {
-                    let result = self.execute(stmt.clone());
-                    
-                    if let RuntimeValue::Return(val) = result {
-                        return_value = *val;
+    fn run_frames(&mut self, mut frame: Frame) -> Result<RuntimeValue, RuntimeError> {
+        // Замінені хвостовим викликом функції: їхній тип результату перевіряється в кінці
+        let mut replaced: Vec<(Rc<FunctionDecl>, String, Span)> = Vec::new();
+        let value = loop {
+            // Змінні того, хто викликав, лишаються видимі через `callers`
+            let mut func_env = Environment::default();
+            func_env.push(frame.decl.locals.clone(), std::mem::take(&mut frame.scope));
+            let previous_env = std::mem::replace(&mut self.env, func_env); // Зберігаємо старий
+            self.callers.push(previous_env);
+
+            let mut flow = Ok(Flow::Return(RuntimeValue::Void)); // Значення за замовчуванням
+            for stmt in &frame.decl.body {
+                match self.execute(stmt) {
+                    Ok(Flow::Next) => {},
+                    Err(e) => {
+                        flow = Err(e.in_function(&frame.name, frame.span));
                         break;
-                    }
+                    },
+                    done => {
+                        flow = done;
+                        break;
+                    },
                 }
-
-                self.env = previous_env;
-                return return_value;
-            },
-            _ => {
-                error!("Undefined function '{}' (ID: {})", func_name, func_id);
-                return RuntimeValue::Null;
             }
-        }
-    }
 
-    fn apply_binary_op(&self, left: RuntimeValue, op: String, right: RuntimeValue) -> RuntimeValue {
-        match (left, right) {
-            (RuntimeValue::Number(a), RuntimeValue::Number(b)) => match op.as_str() {
-                "+" => RuntimeValue::Number(a + b),
-                "-" => RuntimeValue::Number(a - b),
-                "*" => RuntimeValue::Number(a * b),
-                "/" => RuntimeValue::Number(a / b),
-                
-                "<" => RuntimeValue::Bool(a < b),
-                ">" => RuntimeValue::Bool(a > b),
-                "<=" => RuntimeValue::Bool(a <= b),
-                ">=" => RuntimeValue::Bool(a >= b),
-                "==" => RuntimeValue::Bool(a == b),
-                "!=" => RuntimeValue::Bool(a != b),
-                
-                _ => RuntimeValue::Null,
-            },
-            (RuntimeValue::String(a), RuntimeValue::String(b)) => {
-                if op == "+" { 
-                    RuntimeValue::String(format!("{}{}", a, b)) 
-                } else if op == "==" {
-                    RuntimeValue::Bool(a == b)
-                } else if op == "!=" {
-                    RuntimeValue::Bool(a != b)
-                } else { 
-                    RuntimeValue::Null 
+            self.env = self.callers.pop().expect("caller environment");
+
+            match flow? {
+                Flow::TailCall(next) => {
+                    if !replaced.iter().any(|(decl, ..)| Rc::ptr_eq(decl, &frame.decl)) {
+                        replaced.push((frame.decl.clone(), frame.name.clone(), frame.span));
+                    }
+                    frame = *next;
                 }
-            },
-            _ => {
-                error!("Invalid operands for operator {}", op);
-                RuntimeValue::Null
+                Flow::Return(value) => break value,
+                Flow::Next => unreachable!("a function body ends with a return"),
+            }
+        };
+
+        let current = (frame.decl, frame.name, frame.span);
+        for (decl, func_name, span) in std::iter::once(&current).chain(replaced.iter().rev()) {
+            if let Some(expected) = &decl.return_type
+                && !value.matches_type(expected)
+            {
+                return Err(RuntimeError::new(format!(
+                    "Function '{}' must return {}, got {}",
+                    func_name, self.type_label(expected), value.type_name()
+                )).at(*span).in_function(func_name, *span));
             }
         }
+        Ok(value)
     }
-}
\ No newline at end of file
+}
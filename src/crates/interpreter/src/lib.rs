@@ -1 +1,18 @@
-pub mod interpreter;
\ No newline at end of file
+pub mod interpreter;
+pub mod error;
+pub mod checker;
+pub mod bytecode;
+pub mod compiler;
+pub mod vm;
+pub mod resolver;
+pub mod limits;
+pub mod permissions;
+pub mod natives;
+pub mod convert;
+pub mod console;
+pub mod math;
+pub mod strings;
+pub mod casts;
+pub mod files;
+pub mod dates;
+pub mod env_natives;
\ No newline at end of file
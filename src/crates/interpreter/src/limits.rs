@@ -0,0 +1,115 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+use crate::error::{Limit, RuntimeError};
+use crate::interpreter::{Instance, RuntimeValue};
+
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 1000;
+
+/// How many steps pass between two looks at the clock.
+const CLOCK_INTERVAL: u64 = 1024;
+
+/// Bounds a script runs under, shared by `Interpreter` and `Vm`. Exceeding
+/// any of them ends the run with an error whose `limit` is set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Limits {
+    /// Nested (non-tail) calls of script functions allowed at once.
+    pub max_call_depth: usize,
+    /// Statements and expressions the `Interpreter` evaluates, or
+    /// instructions the `Vm` executes, per run.
+    pub max_steps: Option<u64>,
+    /// Largest value a script may build, in bytes of string data (summed
+    /// over the items of a list and the fields of a struct).
+    pub max_value_size: Option<usize>,
+    /// Wall-clock time a run may take.
+    pub timeout: Option<Duration>,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+            max_steps: None,
+            max_value_size: None,
+            timeout: None,
+        }
+    }
+}
+
+impl Limits {
+    pub(crate) fn stack_overflow(&self) -> RuntimeError {
+        RuntimeError::limit_exceeded(
+            Limit::CallDepth,
+            format!("Stack overflow: maximum call depth of {} exceeded", self.max_call_depth),
+        )
+    }
+
+    /// Rejects a value freshly built by the script that is over `max_value_size`.
+    pub(crate) fn check_size(&self, value: &RuntimeValue) -> Result<(), RuntimeError> {
+        let Some(max) = self.max_value_size else {
+            return Ok(());
+        };
+        let size = size_of(value);
+        if size > max {
+            return Err(RuntimeError::limit_exceeded(
+                Limit::ValueSize,
+                format!("Value size limit of {} bytes exceeded: {} bytes", max, size),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A struct instance reachable more than once, or from its own fields,
+/// counts once.
+fn size_of(value: &RuntimeValue) -> usize {
+    fn walk(value: &RuntimeValue, seen: &mut HashSet<*const RefCell<Instance>>) -> usize {
+        match value {
+            RuntimeValue::String(s) => s.len(),
+            RuntimeValue::List(items) => items.iter().map(|item| walk(item, seen)).sum(),
+            RuntimeValue::StructInstance(instance) if seen.insert(Rc::as_ptr(instance)) => {
+                instance.borrow().fields.values().map(|field| walk(field, seen)).sum()
+            }
+            _ => 0,
+        }
+    }
+    walk(value, &mut HashSet::new())
+}
+
+/// Steps taken and the deadline of one run.
+#[derive(Debug)]
+pub(crate) struct Budget {
+    steps: u64,
+    deadline: Option<Instant>,
+}
+
+impl Budget {
+    pub(crate) fn start(limits: &Limits) -> Self {
+        Budget {
+            steps: 0,
+            // Дедлайн, що не вміщається в `Instant`, не настане ніколи
+            deadline: limits.timeout.and_then(|timeout| Instant::now().checked_add(timeout)),
+        }
+    }
+
+    pub(crate) fn step(&mut self, limits: &Limits) -> Result<(), RuntimeError> {
+        self.steps += 1;
+        if let Some(max) = limits.max_steps
+            && self.steps > max
+        {
+            return Err(RuntimeError::limit_exceeded(Limit::Steps, format!("Step limit of {} exceeded", max)));
+        }
+        if self.steps.is_multiple_of(CLOCK_INTERVAL)
+            && let Some(deadline) = self.deadline
+            && Instant::now() >= deadline
+        {
+            let timeout = limits.timeout.unwrap_or_default();
+            return Err(RuntimeError::limit_exceeded(
+                Limit::Timeout,
+                format!("Time limit of {}s exceeded", timeout.as_secs_f64()),
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use lexer::ast::DataType;
+use crate::error::RuntimeError;
+use crate::interpreter::RuntimeValue;
+use crate::natives::{Arity, Native, NativeContext, Natives};
+
+pub const SQRT: u32 = 310;
+pub const POW: u32 = 311;
+pub const ABS: u32 = 312;
+pub const FLOOR: u32 = 313;
+pub const CEIL: u32 = 314;
+pub const ROUND: u32 = 315;
+pub const MIN: u32 = 316;
+pub const MAX: u32 = 317;
+pub const RANDOM: u32 = 318;
+
+/// Registers the math natives; `random` starts from `seed`, so equal seeds
+/// give equal sequences.
+pub fn register(natives: &mut Natives, seed: u64) {
+    let number = Some(DataType::Float);
+    natives.register(SQRT, Native::new("sqrt", Arity::Exact(1), sqrt).param("x", number.clone()).returns(DataType::Float));
+    natives.register(
+        POW,
+        Native::new("pow", Arity::Exact(2), |_, args| Ok(RuntimeValue::Number(float(&args[0])?.powf(float(&args[1])?))))
+            .param("base", number.clone())
+            .param("exponent", number.clone())
+            .returns(DataType::Float),
+    );
+    // Ціле лишається цілим, тож тип результату статично невідомий
+    natives.register(ABS, Native::new("abs", Arity::Exact(1), |_, args| unary(&args, f64::abs)).param("x", number.clone()));
+    natives.register(FLOOR, Native::new("floor", Arity::Exact(1), |_, args| unary(&args, f64::floor)).param("x", number.clone()).returns(DataType::Int));
+    natives.register(CEIL, Native::new("ceil", Arity::Exact(1), |_, args| unary(&args, f64::ceil)).param("x", number.clone()).returns(DataType::Int));
+    natives.register(ROUND, Native::new("round", Arity::Exact(1), |_, args| unary(&args, f64::round)).param("x", number.clone()).returns(DataType::Int));
+    natives.register(MIN, Native::new("min", Arity::AtLeast(1), |_, args| fold(&args, f64::min)).param("values", number.clone()));
+    natives.register(MAX, Native::new("max", Arity::AtLeast(1), |_, args| fold(&args, f64::max)).param("values", number));
+
+    let state = Rc::new(Cell::new(seed));
+    natives.register(
+        RANDOM,
+        Native::new("random", Arity::Exact(0), move |_, _| Ok(RuntimeValue::Number(next_float(&state))))
+            .returns(DataType::Float),
+    );
+}
+
+fn float(value: &RuntimeValue) -> Result<f64, RuntimeError> {
+    f64::try_from(value.clone())
+}
+
+fn unary(args: &[RuntimeValue], f: fn(f64) -> f64) -> Result<RuntimeValue, RuntimeError> {
+    Ok(RuntimeValue::Number(f(float(&args[0])?)))
+}
+
+fn fold(args: &[RuntimeValue], f: fn(f64, f64) -> f64) -> Result<RuntimeValue, RuntimeError> {
+    let mut result = float(&args[0])?;
+    for arg in &args[1..] {
+        result = f(result, float(arg)?);
+    }
+    Ok(RuntimeValue::Number(result))
+}
+
+fn sqrt(_: &mut NativeContext, args: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    let x = float(&args[0])?;
+    if x < 0.0 {
+        return Err(RuntimeError::new(format!("Cannot take the square root of negative number {}", x)));
+    }
+    Ok(RuntimeValue::Number(x.sqrt()))
+}
+
+/// SplitMix64: one step of the generator, as a float in `[0, 1)`.
+fn next_float(state: &Cell<u64>) -> f64 {
+    let next = state.get().wrapping_add(0x9E37_79B9_7F4A_7C15);
+    state.set(next);
+    let mut z = next;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^= z >> 31;
+    (z >> 11) as f64 / (1u64 << 53) as f64
+}
@@ -0,0 +1,255 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use lexer::ast::DataType;
+use unicode_segmentation::UnicodeSegmentation;
+use lexer::tokenizer::std_ids;
+use crate::console::{InputSource, OutputSink};
+use crate::error::RuntimeError;
+use crate::interpreter::{RuntimeValue, format_value, type_label};
+use crate::casts;
+use crate::dates::{self, DateNames};
+use crate::env_natives;
+use crate::files;
+use crate::math;
+use crate::strings;
+use crate::permissions::{Permission, Permissions};
+
+/// How many arguments a native takes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Arity {
+    Exact(usize),
+    /// Between the two bounds, inclusive.
+    Range(usize, usize),
+    AtLeast(usize),
+}
+
+impl Arity {
+    pub fn accepts(&self, count: usize) -> bool {
+        match *self {
+            Arity::Exact(n) => count == n,
+            Arity::Range(min, max) => (min..=max).contains(&count),
+            Arity::AtLeast(min) => count >= min,
+        }
+    }
+}
+
+fn arguments(count: usize) -> String {
+    if count == 1 { "1 argument".to_string() } else { format!("{} arguments", count) }
+}
+
+/// The text of a string argument; `Natives::call` has already checked its type.
+pub(crate) fn text(value: &RuntimeValue) -> &str {
+    match value {
+        RuntimeValue::String(s) => s,
+        _ => unreachable!("argument type is checked by Natives::call"),
+    }
+}
+
+impl fmt::Display for Arity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Arity::Exact(n) => write!(f, "{}", arguments(n)),
+            Arity::Range(0, max) => write!(f, "at most {}", arguments(max)),
+            Arity::Range(min, max) => write!(f, "{} to {}", min, arguments(max)),
+            Arity::AtLeast(min) => write!(f, "at least {}", arguments(min)),
+        }
+    }
+}
+
+/// What a native sees of the engine calling it.
+pub struct NativeContext<'a> {
+    /// Localized spellings of builtin types, for printing values.
+    pub type_names: &'a HashMap<DataType, String>,
+    /// Localized month and weekday names, for formatting dates.
+    pub date_names: &'a DateNames,
+    pub permissions: &'a Permissions,
+    pub output: &'a mut dyn OutputSink,
+    pub input: &'a mut dyn InputSource,
+}
+
+pub type NativeFn = Rc<dyn Fn(&mut NativeContext, Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError>>;
+
+/// A builtin function, called by the std lexeme ID its spellings map to.
+///
+/// Arguments reach `func` already checked against `arity` and `params`,
+/// and only once `permission` is granted.
+#[derive(Clone)]
+pub struct Native {
+    /// Name used in error messages, whatever the script's language.
+    pub name: String,
+    pub arity: Arity,
+    /// Parameter names and types, `None` accepting any value. With
+    /// `Arity::AtLeast` the last parameter covers the remaining arguments.
+    pub params: Vec<(String, Option<DataType>)>,
+    /// Result type the `TypeChecker` assumes, `None` if unknown.
+    pub returns: Option<DataType>,
+    pub permission: Option<Permission>,
+    pub func: NativeFn,
+}
+
+impl Native {
+    pub fn new(
+        name: &str,
+        arity: Arity,
+        func: impl Fn(&mut NativeContext, Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> + 'static,
+    ) -> Self {
+        Native {
+            name: name.to_string(),
+            arity,
+            params: Vec::new(),
+            returns: None,
+            permission: None,
+            func: Rc::new(func),
+        }
+    }
+
+    pub fn param(mut self, name: &str, data_type: Option<DataType>) -> Self {
+        self.params.push((name.to_string(), data_type));
+        self
+    }
+
+    pub fn returns(mut self, data_type: DataType) -> Self {
+        self.returns = Some(data_type);
+        self
+    }
+
+    pub fn needs(mut self, permission: Permission) -> Self {
+        self.permission = Some(permission);
+        self
+    }
+
+    /// Name and declared type of argument `index`.
+    pub fn param_at(&self, index: usize) -> Option<&(String, Option<DataType>)> {
+        match self.arity {
+            Arity::AtLeast(_) => self.params.get(index).or(self.params.last()),
+            _ => self.params.get(index),
+        }
+    }
+}
+
+/// Native functions by std lexeme ID, shared by the `TypeChecker`, the
+/// `Interpreter` and the `Vm`. A new builtin is registered here and given
+/// `NativeFunc` rows in the lexicon; the engines need no changes.
+#[derive(Clone)]
+pub struct Natives {
+    by_id: HashMap<u32, Native>,
+}
+
+impl Default for Natives {
+    fn default() -> Self {
+        Natives::standard()
+    }
+}
+
+impl Natives {
+    pub fn empty() -> Self {
+        Natives { by_id: HashMap::new() }
+    }
+
+    /// The standard natives, with `random` seeded from the clock.
+    pub fn standard() -> Self {
+        let seed = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_nanos() as u64);
+        Natives::with_seed(seed)
+    }
+
+    /// `print`, `input`, `len`, the `math`, `strings`, `casts`, `files` and `dates` natives, with `random` seeded by `seed`.
+    pub fn with_seed(seed: u64) -> Self {
+        let mut natives = Natives::empty();
+        natives.register(
+            std_ids::PRINT,
+            Native::new("print", Arity::AtLeast(0), print)
+                .param("value", None)
+                .needs(Permission::Output),
+        );
+        natives.register(
+            std_ids::INPUT,
+            Native::new("input", Arity::Range(0, 1), input)
+                .param("prompt", None)
+                .returns(DataType::String)
+                .needs(Permission::Input),
+        );
+        natives.register(
+            std_ids::LEN,
+            Native::new("len", Arity::Exact(1), len)
+                .param("value", None)
+                .returns(DataType::Int),
+        );
+        math::register(&mut natives, seed);
+        strings::register(&mut natives);
+        casts::register(&mut natives);
+        files::register(&mut natives);
+        dates::register(&mut natives);
+        env_natives::register(&mut natives);
+        natives
+    }
+
+    /// Binds `native` to `id`, replacing and returning a previous one.
+    pub fn register(&mut self, id: u32, native: Native) -> Option<Native> {
+        self.by_id.insert(id, native)
+    }
+
+    pub fn get(&self, id: u32) -> Option<&Native> {
+        self.by_id.get(&id)
+    }
+
+    /// Runs native `id` on evaluated arguments; `name` is how the script
+    /// spelled it, reported if nothing is registered under `id`.
+    pub(crate) fn call(&self, id: u32, name: &str, args: Vec<RuntimeValue>, ctx: &mut NativeContext) -> Result<RuntimeValue, RuntimeError> {
+        let Some(native) = self.get(id) else {
+            return Err(RuntimeError::new(format!("Unknown native function '{}'", name)));
+        };
+        if !native.arity.accepts(args.len()) {
+            return Err(RuntimeError::new(format!(
+                "Function '{}' expects {}, got {}",
+                native.name, native.arity, args.len()
+            )));
+        }
+        for (index, arg) in args.iter().enumerate() {
+            if let Some((param, Some(expected))) = native.param_at(index)
+                && !arg.matches_type(expected)
+            {
+                return Err(RuntimeError::new(format!(
+                    "Argument '{}' of '{}' expects {}, got {}",
+                    param, native.name, type_label(ctx.type_names, expected), arg.type_name()
+                )));
+            }
+        }
+        if let Some(permission) = native.permission {
+            ctx.permissions.check(permission, &native.name)?;
+        }
+        (native.func)(ctx, args)
+    }
+}
+
+fn print(ctx: &mut NativeContext, args: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    let output: Vec<String> = args.into_iter().map(|v| format_value(ctx.type_names, v)).collect();
+    ctx.output.write(&format!("{}\n", output.join(" ")))
+        .map_err(|e| RuntimeError::new(format!("Failed to write output: {}", e)))?;
+    Ok(RuntimeValue::Void)
+}
+
+fn input(ctx: &mut NativeContext, args: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    if let Some(RuntimeValue::String(prompt)) = args.first() {
+        ctx.output.write(prompt)
+            .map_err(|e| RuntimeError::new(format!("Failed to write prompt: {}", e)))?;
+    }
+    ctx.output.flush()
+        .map_err(|e| RuntimeError::new(format!("Failed to write prompt: {}", e)))?;
+    let line = ctx.input.read_line()
+        .map_err(|e| RuntimeError::new(format!("Failed to read input: {}", e)))?;
+    Ok(RuntimeValue::String(line.trim().to_string()))
+}
+
+/// Characters (grapheme clusters) of a string, or items of a list.
+fn len(_: &mut NativeContext, args: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    match &args[0] {
+        RuntimeValue::String(s) => Ok(RuntimeValue::Number(s.graphemes(true).count() as f64)),
+        RuntimeValue::List(items) => Ok(RuntimeValue::Number(items.len() as f64)),
+        other => Err(RuntimeError::new(format!(
+            "Argument 'value' of 'len' expects String or List, got {}",
+            other.type_name()
+        ))),
+    }
+}
@@ -0,0 +1,100 @@
+use std::collections::HashSet;
+use std::fmt;
+use crate::error::RuntimeError;
+
+/// A capability a native function needs before it runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Permission {
+    /// Reading stdin (`input`).
+    Input,
+    /// Writing stdout (`print`).
+    Output,
+    Read,
+    Write,
+    Env,
+}
+
+impl Permission {
+    pub const ALL: [Permission; 5] = [
+        Permission::Input,
+        Permission::Output,
+        Permission::Read,
+        Permission::Write,
+        Permission::Env,
+    ];
+
+    /// The CLI switch that changes this permission from its default.
+    pub fn flag(&self) -> &'static str {
+        match self {
+            Permission::Input => "--deny-input",
+            Permission::Output => "--deny-output",
+            Permission::Read => "--allow-read",
+            Permission::Write => "--allow-write",
+            Permission::Env => "--allow-env",
+        }
+    }
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Permission::Input => write!(f, "input"),
+            Permission::Output => write!(f, "output"),
+            Permission::Read => write!(f, "read"),
+            Permission::Write => write!(f, "write"),
+            Permission::Env => write!(f, "env"),
+        }
+    }
+}
+
+/// Capabilities granted to a script. By default it may use the console
+/// (`input`, `print`) and nothing else.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Permissions {
+    granted: HashSet<Permission>,
+}
+
+impl Default for Permissions {
+    fn default() -> Self {
+        Permissions::none().allow(Permission::Input).allow(Permission::Output)
+    }
+}
+
+impl Permissions {
+    pub fn none() -> Self {
+        Permissions { granted: HashSet::new() }
+    }
+
+    pub fn all() -> Self {
+        Permissions { granted: Permission::ALL.into_iter().collect() }
+    }
+
+    pub fn allow(mut self, permission: Permission) -> Self {
+        self.granted.insert(permission);
+        self
+    }
+
+    pub fn deny(mut self, permission: Permission) -> Self {
+        self.granted.remove(&permission);
+        self
+    }
+
+    pub fn is_allowed(&self, permission: Permission) -> bool {
+        self.granted.contains(&permission)
+    }
+
+    /// Fails unless `permission` is granted; `function` is the native asking.
+    pub(crate) fn check(&self, permission: Permission, function: &str) -> Result<(), RuntimeError> {
+        if self.is_allowed(permission) {
+            return Ok(());
+        }
+        let hint = match permission {
+            Permission::Input | Permission::Output => format!("it is disabled by {}", permission.flag()),
+            _ => format!("run with {}", permission.flag()),
+        };
+        Err(RuntimeError::new(format!(
+            "Permission denied: '{}' needs {} access, {}",
+            function, permission, hint
+        )))
+    }
+}
@@ -0,0 +1,335 @@
+use std::collections::HashSet;
+use std::rc::{Rc, Weak};
+use lexer::ast::{DataType, Expr, Stmt};
+use lexer::glossary::Glossary;
+use lexer::tokenizer::{Span, std_ids};
+use tracing::{info, debug};
+
+/// Where a name is read from or written to, decided once before execution.
+#[derive(Debug, Clone)]
+pub enum Slot {
+    /// `depth` scopes out from the innermost one, at `index` within it.
+    Local { depth: usize, index: usize },
+    Global(usize),
+    /// A name a function reads without declaring it: looked up in the scopes
+    /// of its callers, the innermost first, then in the global `slot`.
+    Dynamic { name: String, slot: usize },
+    /// The function being declared, or one declared by an enclosing function:
+    /// their bindings live in another call's scopes, so they are bound directly.
+    Function(Weak<FunctionDecl>),
+}
+
+#[derive(Debug)]
+pub enum ResolvedExpr {
+    Number(f64),
+    String(String),
+    Variable { name: String, slot: Slot, span: Span },
+    Binary { left: Box<ResolvedExpr>, op: String, right: Box<ResolvedExpr>, span: Span },
+    Member { object: Box<ResolvedExpr>, member: String, canonical: String, span: Span },
+    Call(Call),
+    List { items: Vec<ResolvedExpr>, span: Span },
+    Index { object: Box<ResolvedExpr>, index: Box<ResolvedExpr>, span: Span },
+}
+
+#[derive(Debug)]
+pub struct Call {
+    pub func_id: u32,
+    pub name: String,
+    /// Canonical name, looked up among struct constructors first.
+    pub canonical: String,
+    /// `None` for native functions.
+    pub callee: Option<Slot>,
+    pub args: Vec<ResolvedExpr>,
+    pub span: Span,
+}
+
+#[derive(Debug)]
+pub enum ResolvedStmt {
+    Let { name: String, mutable: bool, data_type: Option<DataType>, value: Option<ResolvedExpr>, slot: Slot, span: Span },
+    Assign { name: String, value: ResolvedExpr, slot: Slot, span: Span },
+    SetField { object: ResolvedExpr, member: String, canonical: String, value: ResolvedExpr, span: Span },
+    Function { decl: Rc<FunctionDecl>, slot: Slot },
+    Struct { name: String, fields: Vec<(String, DataType)> },
+    Expression(ResolvedExpr),
+    If { condition: ResolvedExpr, then_branch: Block, else_branch: Option<Block> },
+    While { condition: ResolvedExpr, body: Block },
+    Return(Option<ResolvedExpr>),
+    /// `return f(...)` inside a function: unless `f` turns out to be a struct
+    /// constructor, the call replaces the current one instead of nesting.
+    TailCall(Call),
+}
+
+/// Statements of a scope and the canonical names of the local slots they declare.
+#[derive(Debug)]
+pub struct Block {
+    pub statements: Vec<ResolvedStmt>,
+    pub locals: Rc<[String]>,
+}
+
+/// Parameters and body share the call's scope: parameters take the first slots.
+#[derive(Debug)]
+pub struct FunctionDecl {
+    pub name: String,
+    pub params: Vec<(String, DataType)>,
+    pub return_type: Option<DataType>,
+    pub body: Vec<ResolvedStmt>,
+    pub locals: Rc<[String]>,
+}
+
+/// Functions are values compared by identity.
+impl PartialEq for FunctionDecl {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+struct Local {
+    name: String,
+    /// Set for the binding a function declaration creates.
+    function: Option<Weak<FunctionDecl>>,
+}
+
+/// Scopes of the function being resolved; the program's top level is the
+/// outermost one, where names outside any block are globals.
+struct FunctionScope {
+    scopes: Vec<Vec<Local>>,
+    own: Option<(String, Weak<FunctionDecl>)>,
+}
+
+/// Static pass binding every variable to a `Slot` for the `Interpreter`.
+///
+/// Scoping is the same as the `Compiler`'s: a function sees its own locals
+/// and the functions declared around it; any other name is looked up at run
+/// time in the scopes of its callers, then among the globals.
+pub struct Resolver<'a> {
+    glossary: &'a Glossary,
+    globals: &'a mut Vec<String>,
+    free_names: &'a mut HashSet<String>,
+    functions: Vec<FunctionScope>,
+}
+
+impl<'a> Resolver<'a> {
+    /// `globals` are the canonical names of the global slots, extended with
+    /// the ones `statements` introduce; `free_names` collects the names
+    /// functions read from their callers' scopes.
+    pub fn new(glossary: &'a Glossary, globals: &'a mut Vec<String>, free_names: &'a mut HashSet<String>) -> Self {
+        Resolver {
+            glossary,
+            globals,
+            free_names,
+            functions: vec![FunctionScope { scopes: Vec::new(), own: None }],
+        }
+    }
+
+    pub fn resolve(mut self, statements: &[Stmt]) -> Vec<ResolvedStmt> {
+        info!("--- Resolver Started ---");
+        let resolved = statements.iter().map(|stmt| self.statement(stmt)).collect();
+        info!("--- Resolver Finished: {} globals ---", self.globals.len());
+        resolved
+    }
+
+    fn canonical(&self, name: &str) -> String {
+        self.glossary.canonical(name).to_string()
+    }
+
+    fn canonical_type(&self, data_type: &DataType) -> DataType {
+        match data_type {
+            DataType::Custom(name) => DataType::Custom(self.canonical(name)),
+            other => other.clone(),
+        }
+    }
+
+    fn canonical_fields(&self, fields: &[(String, DataType)]) -> Vec<(String, DataType)> {
+        fields.iter().map(|(name, t)| (self.canonical(name), self.canonical_type(t))).collect()
+    }
+
+    fn current(&mut self) -> &mut FunctionScope {
+        self.functions.last_mut().expect("function scope")
+    }
+
+    fn global_slot(&mut self, name: &str) -> usize {
+        match self.globals.iter().position(|g| g == name) {
+            Some(slot) => slot,
+            None => {
+                self.globals.push(name.to_string());
+                self.globals.len() - 1
+            }
+        }
+    }
+
+    fn lookup(&mut self, name: &str) -> Slot {
+        let current = self.functions.last().expect("function scope");
+        for (depth, scope) in current.scopes.iter().rev().enumerate() {
+            if let Some(index) = scope.iter().position(|l| l.name == name) {
+                return Slot::Local { depth, index };
+            }
+        }
+        for (i, function) in self.functions.iter().rev().enumerate() {
+            if let Some((own, decl)) = &function.own && own == name {
+                return Slot::Function(decl.clone());
+            }
+            if i == 0 {
+                continue;
+            }
+            let found = function.scopes.iter().rev().find_map(|s| s.iter().find(|l| l.name == name));
+            if let Some(local) = found {
+                match &local.function {
+                    Some(decl) => return Slot::Function(decl.clone()),
+                    // Змінну зовнішньої функції шукають серед тих, хто викликав
+                    None => break,
+                }
+            }
+        }
+        let slot = self.global_slot(name);
+        if self.functions.len() == 1 {
+            return Slot::Global(slot);
+        }
+        self.free_names.insert(name.to_string());
+        Slot::Dynamic { name: name.to_string(), slot }
+    }
+
+    /// Binds `name` in the innermost scope, reusing the slot of a previous
+    /// declaration of the same name there.
+    fn declare(&mut self, name: String, function: Option<Weak<FunctionDecl>>) -> Slot {
+        let Some(scope) = self.current().scopes.last_mut() else {
+            return Slot::Global(self.global_slot(&name));
+        };
+        let index = match scope.iter().position(|l| l.name == name) {
+            Some(index) => {
+                scope[index].function = function;
+                index
+            }
+            None => {
+                scope.push(Local { name, function });
+                scope.len() - 1
+            }
+        };
+        Slot::Local { depth: 0, index }
+    }
+
+    fn statement(&mut self, stmt: &Stmt) -> ResolvedStmt {
+        match stmt {
+            Stmt::VariableDeclaration { name, mutable, data_type, value, span } => {
+                let value = value.as_ref().map(|v| self.expr(v));
+                let data_type = data_type.as_ref().map(|t| self.canonical_type(t));
+                let slot = self.declare(self.canonical(name), None);
+                ResolvedStmt::Let { name: name.clone(), mutable: *mutable, data_type, value, slot, span: *span }
+            }
+            Stmt::Assignment { name, value, span } => {
+                let value = self.expr(value);
+                let name = self.canonical(name);
+                let slot = self.lookup(&name);
+                ResolvedStmt::Assign { name, value, slot, span: *span }
+            }
+            Stmt::FieldAssignment { object, member, value, span } => ResolvedStmt::SetField {
+                object: self.expr(object),
+                member: member.clone(),
+                canonical: self.canonical(member),
+                value: self.expr(value),
+                span: *span,
+            },
+            Stmt::FunctionDeclaration { name, params, return_type, body, .. } => {
+                debug!("Resolve function: {}", name);
+                let decl = self.function(name, params, return_type, body);
+                let slot = self.declare(self.canonical(name), Some(Rc::downgrade(&decl)));
+                ResolvedStmt::Function { decl, slot }
+            }
+            Stmt::StructDeclaration { name, fields, .. } => ResolvedStmt::Struct {
+                name: self.canonical(name),
+                fields: self.canonical_fields(fields),
+            },
+            Stmt::Expression(expr) => ResolvedStmt::Expression(self.expr(expr)),
+            Stmt::If { condition, then_branch, else_branch } => ResolvedStmt::If {
+                condition: self.expr(condition),
+                then_branch: self.block(then_branch),
+                else_branch: else_branch.as_ref().map(|stmts| self.block(stmts)),
+            },
+            Stmt::While { condition, body } => ResolvedStmt::While {
+                condition: self.expr(condition),
+                body: self.block(body),
+            },
+            Stmt::Return { value, .. } => match value.as_ref().map(|v| self.expr(v)) {
+                Some(ResolvedExpr::Call(call)) if call.callee.is_some() && self.functions.len() > 1 => {
+                    ResolvedStmt::TailCall(call)
+                }
+                value => ResolvedStmt::Return(value),
+            },
+        }
+    }
+
+    fn function(&mut self, name: &str, params: &[(String, DataType)], return_type: &Option<DataType>, body: &[Stmt]) -> Rc<FunctionDecl> {
+        let params = self.canonical_fields(params);
+        let return_type = return_type.as_ref().map(|t| self.canonical_type(t));
+        let own = self.canonical(name);
+
+        Rc::new_cyclic(|decl| {
+            let locals = params.iter().map(|(param, _)| Local { name: param.clone(), function: None }).collect();
+            self.functions.push(FunctionScope { scopes: vec![locals], own: Some((own, decl.clone())) });
+            let body = body.iter().map(|stmt| self.statement(stmt)).collect();
+            let mut scope = self.functions.pop().expect("function scope");
+            FunctionDecl {
+                name: name.to_string(),
+                params,
+                return_type,
+                body,
+                locals: scope.scopes.pop().map_or_else(|| Rc::from([]), names),
+            }
+        })
+    }
+
+    fn block(&mut self, statements: &[Stmt]) -> Block {
+        self.current().scopes.push(Vec::new());
+        let statements = statements.iter().map(|stmt| self.statement(stmt)).collect();
+        let locals = self.current().scopes.pop().map_or_else(|| Rc::from([]), names);
+        Block { statements, locals }
+    }
+
+    fn expr(&mut self, expr: &Expr) -> ResolvedExpr {
+        match expr {
+            Expr::Number(n) => ResolvedExpr::Number(*n),
+            Expr::StringLiteral(s) => ResolvedExpr::String(s.clone()),
+            Expr::Identifier { name, span } => ResolvedExpr::Variable {
+                name: name.clone(),
+                slot: self.lookup(&self.canonical(name)),
+                span: *span,
+            },
+            Expr::BinaryOp { left, op, right, span } => ResolvedExpr::Binary {
+                left: Box::new(self.expr(left)),
+                op: op.clone(),
+                right: Box::new(self.expr(right)),
+                span: *span,
+            },
+            Expr::MemberAccess { object, member, span } => ResolvedExpr::Member {
+                object: Box::new(self.expr(object)),
+                member: member.clone(),
+                canonical: self.canonical(member),
+                span: *span,
+            },
+            Expr::List { items, span } => ResolvedExpr::List {
+                items: items.iter().map(|item| self.expr(item)).collect(),
+                span: *span,
+            },
+            Expr::Index { object, index, span } => ResolvedExpr::Index {
+                object: Box::new(self.expr(object)),
+                index: Box::new(self.expr(index)),
+                span: *span,
+            },
+            Expr::Call { func_id, func_name, args, span } => {
+                let canonical = self.canonical(func_name);
+                let callee = (!std_ids::is_native(*func_id)).then(|| self.lookup(&canonical));
+                ResolvedExpr::Call(Call {
+                    func_id: *func_id,
+                    name: func_name.clone(),
+                    canonical,
+                    callee,
+                    args: args.iter().map(|arg| self.expr(arg)).collect(),
+                    span: *span,
+                })
+            }
+        }
+    }
+}
+
+fn names(scope: Vec<Local>) -> Rc<[String]> {
+    scope.into_iter().map(|local| local.name).collect()
+}
@@ -0,0 +1,139 @@
+use std::rc::Rc;
+use lexer::ast::DataType;
+use unicode_segmentation::UnicodeSegmentation;
+use crate::error::RuntimeError;
+use crate::interpreter::{RuntimeValue, format_value};
+use crate::natives::{Arity, Native, NativeContext, Natives, text};
+
+pub const SUBSTRING: u32 = 320;
+pub const SPLIT: u32 = 321;
+pub const JOIN: u32 = 322;
+pub const TRIM: u32 = 323;
+pub const UPPER: u32 = 324;
+pub const LOWER: u32 = 325;
+pub const CONTAINS: u32 = 326;
+pub const REPLACE: u32 = 327;
+pub const FIND: u32 = 328;
+
+/// Registers the string natives. Positions and lengths count characters as
+/// a reader sees them (grapheme clusters), not bytes.
+pub fn register(natives: &mut Natives) {
+    let string = Some(DataType::String);
+    let int = Some(DataType::Int);
+    natives.register(
+        SUBSTRING,
+        Native::new("substring", Arity::Range(2, 3), substring)
+            .param("text", string.clone())
+            .param("start", int.clone())
+            .param("end", int)
+            .returns(DataType::String),
+    );
+    natives.register(
+        SPLIT,
+        Native::new("split", Arity::Exact(2), split)
+            .param("text", string.clone())
+            .param("separator", string.clone())
+            .returns(DataType::List),
+    );
+    natives.register(
+        JOIN,
+        Native::new("join", Arity::Exact(2), join)
+            .param("items", Some(DataType::List))
+            .param("separator", string.clone())
+            .returns(DataType::String),
+    );
+    natives.register(TRIM, Native::new("trim", Arity::Exact(1), |_, args| map(&args, |s| s.trim().to_string())).param("text", string.clone()).returns(DataType::String));
+    natives.register(UPPER, Native::new("upper", Arity::Exact(1), |_, args| map(&args, str::to_uppercase)).param("text", string.clone()).returns(DataType::String));
+    natives.register(LOWER, Native::new("lower", Arity::Exact(1), |_, args| map(&args, str::to_lowercase)).param("text", string.clone()).returns(DataType::String));
+    natives.register(
+        CONTAINS,
+        Native::new("contains", Arity::Exact(2), contains)
+            .param("haystack", None)
+            .param("needle", None)
+            .returns(DataType::Bool),
+    );
+    natives.register(
+        REPLACE,
+        Native::new("replace", Arity::Exact(3), replace)
+            .param("text", string.clone())
+            .param("from", string.clone())
+            .param("to", string.clone())
+            .returns(DataType::String),
+    );
+    natives.register(
+        FIND,
+        Native::new("find", Arity::Exact(2), find)
+            .param("text", string.clone())
+            .param("part", string)
+            .returns(DataType::Int),
+    );
+}
+
+fn map(args: &[RuntimeValue], f: impl Fn(&str) -> String) -> Result<RuntimeValue, RuntimeError> {
+    Ok(RuntimeValue::String(f(text(&args[0]))))
+}
+
+/// Characters `start..end`, both clamped to the string, `end` defaulting to its length.
+fn substring(_: &mut NativeContext, args: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    let graphemes: Vec<&str> = text(&args[0]).graphemes(true).collect();
+    let clamp = |value: &RuntimeValue| -> Result<usize, RuntimeError> {
+        Ok(i64::try_from(value.clone())?.clamp(0, graphemes.len() as i64) as usize)
+    };
+    let start = clamp(&args[1])?;
+    let end = match args.get(2) {
+        Some(end) => clamp(end)?,
+        None => graphemes.len(),
+    };
+    Ok(RuntimeValue::String(graphemes[start..end.max(start)].concat()))
+}
+
+/// Parts of the text between separators; an empty separator splits it into characters.
+fn split(_: &mut NativeContext, args: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    let (s, separator) = (text(&args[0]), text(&args[1]));
+    let parts: Vec<RuntimeValue> = if separator.is_empty() {
+        s.graphemes(true).map(|g| RuntimeValue::String(g.to_string())).collect()
+    } else {
+        s.split(separator).map(|part| RuntimeValue::String(part.to_string())).collect()
+    };
+    Ok(RuntimeValue::List(Rc::new(parts)))
+}
+
+fn join(ctx: &mut NativeContext, args: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    let RuntimeValue::List(items) = &args[0] else {
+        unreachable!("argument type is checked by Natives::call");
+    };
+    let parts: Vec<String> = items.iter().map(|item| format_value(ctx.type_names, item.clone())).collect();
+    Ok(RuntimeValue::String(parts.join(text(&args[1]))))
+}
+
+/// A substring of a string, or an item of a list.
+fn contains(_: &mut NativeContext, args: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    match (&args[0], &args[1]) {
+        (RuntimeValue::String(s), RuntimeValue::String(part)) => Ok(RuntimeValue::Bool(s.contains(part.as_str()))),
+        (RuntimeValue::String(_), other) => Err(RuntimeError::new(format!(
+            "Argument 'needle' of 'contains' expects String, got {}",
+            other.type_name()
+        ))),
+        (RuntimeValue::List(items), needle) => Ok(RuntimeValue::Bool(items.contains(needle))),
+        (other, _) => Err(RuntimeError::new(format!(
+            "Argument 'haystack' of 'contains' expects String or List, got {}",
+            other.type_name()
+        ))),
+    }
+}
+
+/// Every occurrence of `from` replaced; an empty `from` leaves the text as it is.
+fn replace(_: &mut NativeContext, args: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    let (s, from, to) = (text(&args[0]), text(&args[1]), text(&args[2]));
+    if from.is_empty() {
+        return Ok(RuntimeValue::String(s.to_string()));
+    }
+    Ok(RuntimeValue::String(s.replace(from, to)))
+}
+
+/// Character position of the first occurrence of `part`, or -1.
+fn find(_: &mut NativeContext, args: Vec<RuntimeValue>) -> Result<RuntimeValue, RuntimeError> {
+    let (s, part) = (text(&args[0]), text(&args[1]));
+    let position = s.find(part).map_or(-1.0, |byte| s[..byte].graphemes(true).count() as f64);
+    Ok(RuntimeValue::Number(position))
+}
@@ -0,0 +1,478 @@
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use lexer::ast::{DataType, Stmt};
+use lexer::glossary::Glossary;
+use lexer::tokenizer::Span;
+use tracing::info;
+use crate::bytecode::{CallTarget, Chunk, CompiledFunction, LocalName, Op, Program, StructDef};
+use crate::compiler::Compiler;
+use crate::dates::DateNames;
+use crate::error::RuntimeError;
+use crate::limits::{Budget, Limits};
+use crate::console::{InputSource, OutputSink, StdinSource, StdoutSink};
+use crate::natives::{NativeContext, Natives};
+use crate::permissions::Permissions;
+use crate::interpreter::{RuntimeValue, binary_op, get_field, get_index, is_truthy, set_field, type_label};
+
+struct Global {
+    value: RuntimeValue,
+    mutable: bool,
+    data_type: Option<DataType>,
+}
+
+/// Struct constructor or function resolved by `Op::Callee`.
+enum Target {
+    Struct(Rc<StructDef>),
+    Function(Rc<CompiledFunction>),
+}
+
+/// The call that created a frame, for return checks and error stack traces.
+#[derive(Clone)]
+struct CallInfo {
+    function: Rc<CompiledFunction>,
+    name: Rc<str>,
+    span: Span,
+    ctx: Option<Span>,
+}
+
+/// A suspended caller.
+struct Frame {
+    chunk: Rc<Chunk>,
+    ip: usize,
+    base: usize,
+    call: Option<CallInfo>,
+    /// Calls this frame took over through tail calls, each function once;
+    /// their return types are checked when the frame returns.
+    replaced: Vec<CallInfo>,
+    /// Locals live at the call the frame is suspended in, which the callee
+    /// reads by name.
+    locals: Rc<[LocalName]>,
+}
+
+/// Stack machine running programs from the `Compiler`, with the same
+/// observable behavior as `Interpreter`.
+pub struct Vm {
+    /// Localized spellings of builtin types (`ціле`, `int`) used when printing values.
+    pub type_names: HashMap<DataType, String>,
+    /// Localized month and weekday names used by `format_date`.
+    pub date_names: DateNames,
+    pub limits: Limits,
+    pub permissions: Permissions,
+    pub natives: Natives,
+    pub output: Box<dyn OutputSink>,
+    pub input: Box<dyn InputSource>,
+    glossary: Glossary,
+    global_names: Vec<String>,
+    globals: Vec<Option<Global>>,
+    /// Names functions read from their callers' scopes: a tail call keeps
+    /// the caller's frame while one of its locals has such a name.
+    free_names: HashSet<String>,
+    structs: HashMap<String, Rc<StructDef>>,
+}
+
+impl Default for Vm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Vm {
+    pub fn new() -> Self {
+        Self::with_glossary(Glossary::default())
+    }
+
+    pub fn with_glossary(glossary: Glossary) -> Self {
+        Vm {
+            type_names: HashMap::new(),
+            date_names: DateNames::default(),
+            limits: Limits::default(),
+            permissions: Permissions::default(),
+            natives: Natives::standard(),
+            output: Box::new(StdoutSink),
+            input: Box::new(StdinSource),
+            glossary,
+            global_names: Vec::new(),
+            globals: Vec::new(),
+            free_names: HashSet::new(),
+            structs: HashMap::new(),
+        }
+    }
+
+    /// Compiles and runs `statements`; globals stay defined for the next call.
+    pub fn interpret(&mut self, statements: Vec<Stmt>) -> Result<(), RuntimeError> {
+        let program = Compiler::new(self.glossary.clone(), self.global_names.clone()).compile(&statements);
+        self.run(&program)
+    }
+
+    pub fn global(&self, name: &str) -> Option<RuntimeValue> {
+        let name = self.glossary.canonical(name);
+        let slot = self.global_names.iter().position(|g| g == name)?;
+        self.globals.get(slot)?.as_ref().map(|g| g.value.clone())
+    }
+
+    pub fn run(&mut self, program: &Program) -> Result<(), RuntimeError> {
+        info!("--- VM Started ---");
+        self.global_names = program.globals.clone();
+        self.globals.resize_with(program.globals.len(), || None);
+        self.free_names.extend(program.free_names.iter().cloned());
+
+        let mut frames = Vec::new();
+        let mut current = Frame { chunk: program.main.clone(), ip: 0, base: 0, call: None, replaced: Vec::new(), locals: Rc::default() };
+        let mut budget = Budget::start(&self.limits);
+        let result = self.execute(program, &mut current, &mut frames, &mut budget);
+
+        result.map_err(|mut e| {
+            for frame in std::iter::once(&current).chain(frames.iter().rev()) {
+                if let Some(call) = &frame.call {
+                    e = e.in_function(&call.name, call.span);
+                    if let Some(ctx) = call.ctx {
+                        e = e.or_at(ctx);
+                    }
+                }
+            }
+            e
+        })?;
+        info!("--- VM Finished ---");
+        Ok(())
+    }
+
+    fn execute(
+        &mut self,
+        program: &Program,
+        frame: &mut Frame,
+        frames: &mut Vec<Frame>,
+        budget: &mut Budget,
+    ) -> Result<(), RuntimeError> {
+        let mut stack: Vec<RuntimeValue> = Vec::new();
+        let mut calls: Vec<Target> = Vec::new();
+        let mut chunk = frame.chunk.clone();
+
+        loop {
+            let Some(op) = chunk.code.get(frame.ip) else {
+                return Ok(());
+            };
+            frame.ip += 1;
+            budget.step(&self.limits)?;
+
+            match op {
+                Op::Constant(index) => stack.push(program.constants[*index].clone()),
+                Op::Function(index) => stack.push(RuntimeValue::Compiled(program.functions[*index].clone())),
+                Op::Pop => {
+                    stack.pop();
+                }
+                Op::Truncate(slot) => stack.truncate(frame.base + slot),
+
+                Op::GetLocal(slot) => stack.push(stack[frame.base + slot].clone()),
+                Op::SetLocal(slot) => {
+                    let value = pop(&mut stack);
+                    stack[frame.base + slot] = value;
+                }
+                Op::GetGlobal { slot, name, span } => match &self.globals[*slot] {
+                    Some(global) => stack.push(global.value.clone()),
+                    None => return Err(RuntimeError::new(format!("Undefined variable '{}'", name)).at(*span)),
+                },
+                Op::DefineGlobal { slot, mutable, data_type } => {
+                    let value = pop(&mut stack);
+                    self.globals[*slot] = Some(Global { value, mutable: *mutable, data_type: data_type.clone() });
+                }
+                Op::SetGlobal { slot, span } => {
+                    let value = pop(&mut stack);
+                    self.set_global(&program.globals[*slot], *slot, value, *span)?;
+                }
+                Op::GetDynamic { slot, canonical, name, span } => {
+                    let value = match caller_local(frames, canonical) {
+                        Some((index, _)) => stack[index].clone(),
+                        None => match &self.globals[*slot] {
+                            Some(global) => global.value.clone(),
+                            None => return Err(RuntimeError::new(format!("Undefined variable '{}'", name)).at(*span)),
+                        },
+                    };
+                    stack.push(value);
+                }
+                Op::SetDynamic { slot, canonical, span } => {
+                    let value = pop(&mut stack);
+                    match caller_local(frames, canonical) {
+                        Some((index, local)) => {
+                            check_assigned(&self.type_names, canonical, local.mutable, local.data_type.as_ref(), &value)
+                                .map_err(|e| e.at(*span))?;
+                            stack[index] = value;
+                        }
+                        None => self.set_global(canonical, *slot, value, *span)?,
+                    }
+                }
+
+                Op::CheckDeclared { name, data_type, span } => {
+                    let value = stack.last().expect("value to check");
+                    if !value.matches_type(data_type) {
+                        return Err(RuntimeError::new(format!(
+                            "Variable '{}' is declared as {}, got {}",
+                            name, type_label(&self.type_names, data_type), value.type_name()
+                        )).at(*span));
+                    }
+                }
+                Op::CheckAssigned { name, data_type, span } => {
+                    let value = stack.last().expect("value to check");
+                    check_assigned(&self.type_names, name, true, Some(data_type), value).map_err(|e| e.at(*span))?;
+                }
+
+                Op::Binary { op, span } => {
+                    let right = pop(&mut stack);
+                    let left = pop(&mut stack);
+                    let value = binary_op(left, op, right).map_err(|e| e.or_at(*span))?;
+                    self.limits.check_size(&value).map_err(|e| e.at(*span))?;
+                    stack.push(value);
+                }
+                Op::MakeList { count, span } => {
+                    let items = stack.split_off(stack.len() - count);
+                    let list = RuntimeValue::List(Rc::new(items));
+                    self.limits.check_size(&list).map_err(|e| e.at(*span))?;
+                    stack.push(list);
+                }
+                Op::Index { span } => {
+                    let index = pop(&mut stack);
+                    let object = pop(&mut stack);
+                    stack.push(get_index(&object, &index, *span)?);
+                }
+                Op::GetField { member, canonical, span } => {
+                    let object = pop(&mut stack);
+                    stack.push(get_field(&object, member, canonical, *span)?);
+                }
+                Op::SetField { member, canonical, span } => {
+                    let value = pop(&mut stack);
+                    let object = pop(&mut stack);
+                    let structs = &self.structs;
+                    set_field(&self.type_names, &object, member, canonical, value, *span, |type_name| {
+                        let def = structs.get(type_name)?;
+                        def.fields.iter().find(|(field, _)| **field == **canonical).map(|(_, t)| t.clone())
+                    })?;
+                }
+                Op::DefineStruct(index) => {
+                    let def = program.structs[*index].clone();
+                    self.structs.insert(def.name.clone(), def);
+                }
+
+                Op::Jump(target) => frame.ip = *target,
+                Op::JumpIfFalse(target) => {
+                    if !is_truthy(&pop(&mut stack)) {
+                        frame.ip = *target;
+                    }
+                }
+
+                Op::Callee { name, func_id, struct_name, target, argc, span } => {
+                    if let Some(struct_name) = struct_name
+                        && let Some(def) = self.structs.get(&**struct_name)
+                    {
+                        if *argc != def.fields.len() {
+                            return Err(RuntimeError::new(format!(
+                                "Constructor '{}' expects {} arguments, got {}",
+                                name, def.fields.len(), argc
+                            )).at(*span));
+                        }
+                        calls.push(Target::Struct(def.clone()));
+                        continue;
+                    }
+
+                    let value = match target {
+                        CallTarget::Local(slot) => Some(stack[frame.base + slot].clone()),
+                        CallTarget::Global(slot) => self.globals[*slot].as_ref().map(|g| g.value.clone()),
+                        CallTarget::Function(index) => Some(RuntimeValue::Compiled(program.functions[*index].clone())),
+                        CallTarget::Dynamic { slot, canonical } => match caller_local(frames, canonical) {
+                            Some((index, _)) => Some(stack[index].clone()),
+                            None => self.globals[*slot].as_ref().map(|g| g.value.clone()),
+                        },
+                    };
+                    match value {
+                        Some(RuntimeValue::Compiled(function)) => {
+                            if *argc != function.params.len() {
+                                return Err(RuntimeError::new(format!(
+                                    "Arg count mismatch for '{}'. Expected {}, got {}",
+                                    name, function.params.len(), argc
+                                )).at(*span));
+                            }
+                            calls.push(Target::Function(function));
+                        }
+                        _ => {
+                            return Err(RuntimeError::new(format!("Undefined function '{}' (ID: {})", name, func_id)).at(*span));
+                        }
+                    }
+                }
+                Op::CheckArg { index, name, span } => {
+                    let value = stack.last().expect("argument to check");
+                    let (kind, declared, declared_type) = match calls.last().expect("callee resolved") {
+                        Target::Struct(def) => ("Field", &def.fields[*index].0, &def.fields[*index].1),
+                        Target::Function(function) => ("Argument", &function.params[*index].0, &function.params[*index].1),
+                    };
+                    if !value.matches_type(declared_type) {
+                        return Err(RuntimeError::new(format!(
+                            "{} '{}' of '{}' expects {}, got {}",
+                            kind, declared, name, type_label(&self.type_names, declared_type), value.type_name()
+                        )).at(*span));
+                    }
+                }
+                Op::Call { argc, name, span, ctx, locals } => {
+                    let args_start = stack.len() - argc;
+                    match calls.pop().expect("callee resolved") {
+                        Target::Struct(def) => {
+                            let instance = instantiate(&def, stack.split_off(args_start));
+                            self.limits.check_size(&instance).map_err(|e| e.at(*span))?;
+                            stack.push(instance);
+                        }
+                        Target::Function(function) => {
+                            let call = CallInfo { function, name: name.clone(), span: *span, ctx: *ctx };
+                            chunk = self.enter(call, args_start, locals, frame, frames)?;
+                        }
+                    }
+                }
+                Op::TailCall { argc, name, span, locals } => {
+                    let args_start = stack.len() - argc;
+                    match calls.pop().expect("callee resolved") {
+                        Target::Struct(def) => {
+                            let instance = instantiate(&def, stack.split_off(args_start));
+                            self.limits.check_size(&instance).map_err(|e| e.at(*span))?;
+                            stack.push(instance);
+                            self.return_from(frame, &mut stack)?;
+                            *frame = frames.pop().expect("caller frame");
+                            chunk = frame.chunk.clone();
+                        }
+                        Target::Function(function) if locals.iter().any(|l| self.free_names.contains(&*l.name)) => {
+                            // Виклик може читати наші змінні, тож кадр лишається
+                            let call = CallInfo { function, name: name.clone(), span: *span, ctx: None };
+                            chunk = self.enter(call, args_start, locals, frame, frames)?;
+                        }
+                        Target::Function(function) => {
+                            // Аргументи займають місце локальних змінних поточного виклику
+                            stack.drain(frame.base..args_start);
+                            let previous = frame.call.take().expect("tail call outside of a function");
+                            if !frame.replaced.iter().any(|c| Rc::ptr_eq(&c.function, &previous.function)) {
+                                frame.replaced.push(CallInfo { ctx: None, ..previous.clone() });
+                            }
+                            frame.call = Some(CallInfo { function: function.clone(), name: name.clone(), span: *span, ctx: previous.ctx });
+                            frame.chunk = function.chunk.clone();
+                            frame.ip = 0;
+                            chunk = function.chunk.clone();
+                        }
+                    }
+                }
+                Op::Return => {
+                    self.return_from(frame, &mut stack)?;
+                    *frame = frames.pop().expect("caller frame");
+                    chunk = frame.chunk.clone();
+                }
+
+                Op::CallNative { id, argc, name, span } => {
+                    let args = stack.split_off(stack.len() - argc);
+                    let mut ctx = NativeContext {
+                        type_names: &self.type_names,
+                        date_names: &self.date_names,
+                        permissions: &self.permissions,
+                        output: &mut *self.output,
+                        input: &mut *self.input,
+                    };
+                    let value = self.natives.call(*id, name, args, &mut ctx).map_err(|e| e.at(*span))?;
+                    self.limits.check_size(&value).map_err(|e| e.at(*span))?;
+                    stack.push(value);
+                }
+                Op::Fail { message, span } => {
+                    let error = RuntimeError::new(&**message);
+                    return Err(match span {
+                        Some(span) => error.at(*span),
+                        None => error,
+                    });
+                }
+            }
+        }
+    }
+}
+
+impl Vm {
+    /// Suspends `frame` at a call and starts the called function in its
+    /// place, with the arguments from `base` up as its first slots.
+    fn enter(
+        &self,
+        call: CallInfo,
+        base: usize,
+        locals: &Rc<[LocalName]>,
+        frame: &mut Frame,
+        frames: &mut Vec<Frame>,
+    ) -> Result<Rc<Chunk>, RuntimeError> {
+        if frames.len() >= self.limits.max_call_depth {
+            return Err(self.limits.stack_overflow().at(call.span));
+        }
+        let chunk = call.function.chunk.clone();
+        frame.locals = locals.clone();
+        let callee = Frame { chunk: chunk.clone(), ip: 0, base, call: Some(call), replaced: Vec::new(), locals: Rc::default() };
+        frames.push(std::mem::replace(frame, callee));
+        Ok(chunk)
+    }
+
+    fn set_global(&mut self, name: &str, slot: usize, value: RuntimeValue, span: Span) -> Result<(), RuntimeError> {
+        let global = self.globals[slot].as_mut()
+            .ok_or_else(|| RuntimeError::new(format!("Undefined variable '{}'", name)).at(span))?;
+        check_assigned(&self.type_names, name, global.mutable, global.data_type.as_ref(), &value).map_err(|e| e.at(span))?;
+        global.value = value;
+        Ok(())
+    }
+
+    /// Checks the returned value (top of the stack) against the return types
+    /// of the frame's calls, innermost first, and drops the frame's slots.
+    fn return_from(&self, frame: &mut Frame, stack: &mut Vec<RuntimeValue>) -> Result<(), RuntimeError> {
+        let value = pop(stack);
+        let current = frame.call.as_ref().expect("return outside of a function");
+        let mismatch = std::iter::once(current).chain(frame.replaced.iter().rev()).find(|call| {
+            call.function.return_type.as_ref().is_some_and(|expected| !value.matches_type(expected))
+        });
+        if let Some(call) = mismatch {
+            let expected = call.function.return_type.as_ref().expect("checked above");
+            let error = RuntimeError::new(format!(
+                "Function '{}' must return {}, got {}",
+                call.name, type_label(&self.type_names, expected), value.type_name()
+            )).at(call.span);
+            // Помилка належить виклику, який її спричинив, навіть якщо його замінив хвостовий
+            let call = CallInfo { ctx: current.ctx, ..call.clone() };
+            frame.call = Some(call);
+            return Err(error);
+        }
+        stack.truncate(frame.base);
+        stack.push(value);
+        Ok(())
+    }
+}
+
+/// The innermost local called `name` among the suspended callers: its
+/// position on the stack and how it was declared.
+fn caller_local<'a>(frames: &'a [Frame], name: &str) -> Option<(usize, &'a LocalName)> {
+    frames.iter().rev().find_map(|frame| {
+        let slot = frame.locals.iter().rposition(|local| &*local.name == name)?;
+        Some((frame.base + slot, &frame.locals[slot]))
+    })
+}
+
+fn check_assigned(
+    type_names: &HashMap<DataType, String>,
+    name: &str,
+    mutable: bool,
+    data_type: Option<&DataType>,
+    value: &RuntimeValue,
+) -> Result<(), RuntimeError> {
+    if !mutable {
+        return Err(RuntimeError::immutable(name));
+    }
+    if let Some(data_type) = data_type
+        && !value.matches_type(data_type)
+    {
+        return Err(RuntimeError::new(format!(
+            "Variable '{}' is declared as {}, got {}",
+            name, type_label(type_names, data_type), value.type_name()
+        )));
+    }
+    Ok(())
+}
+
+fn instantiate(def: &StructDef, values: Vec<RuntimeValue>) -> RuntimeValue {
+    let fields = def.fields.iter().map(|(field, _)| field.clone()).zip(values).collect();
+    RuntimeValue::instance(def.name.clone(), fields)
+}
+
+fn pop(stack: &mut Vec<RuntimeValue>) -> RuntimeValue {
+    stack.pop().expect("VM stack underflow")
+}
@@ -1,45 +1,101 @@
-use crate::tokenizer::{Token, std_ids};
+use crate::tokenizer::{Token, Span, std_ids};
 use tracing::{info, trace, error};
+use serde::{Deserialize, Serialize};
+use std::fmt;
 
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum DataType {
     Int,
     Float,
     String,
     Bool,
+    /// Built by `[...]` literals and natives; annotated with the `список` / `list` keyword (`LIST_TYPE`).
+    List,
     Custom(String),
 }
 
-#[derive(Debug, Clone, PartialEq)]
+impl DataType {
+    /// Maps a builtin type keyword (`ціле`, `string`, ...) to its type.
+    pub fn from_std_id(std_id: u32) -> Option<DataType> {
+        match std_id {
+            std_ids::INT_TYPE => Some(DataType::Int),
+            std_ids::FLOAT_TYPE => Some(DataType::Float),
+            std_ids::STRING_TYPE => Some(DataType::String),
+            std_ids::BOOL_TYPE => Some(DataType::Bool),
+            std_ids::LIST_TYPE => Some(DataType::List),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for DataType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataType::Int => write!(f, "Int"),
+            DataType::Float => write!(f, "Float"),
+            DataType::String => write!(f, "String"),
+            DataType::Bool => write!(f, "Bool"),
+            DataType::List => write!(f, "List"),
+            DataType::Custom(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Expr {
     Number(f64),
     StringLiteral(String),
-    Identifier(String),
-    BinaryOp { left: Box<Expr>, op: String, right: Box<Expr> },
+    Identifier { name: String, span: Span },
+    BinaryOp { left: Box<Expr>, op: String, right: Box<Expr>, span: Span },
     Call {
         func_id: u32,
         func_name: String,
         args: Vec<Expr>,
+        span: Span,
     },
     MemberAccess {
         object: Box<Expr>,
         member: String,
+        span: Span,
+    },
+    List { items: Vec<Expr>, span: Span },
+    /// `object[index]`: an element of a list or a character of a string.
+    Index {
+        object: Box<Expr>,
+        index: Box<Expr>,
+        span: Span,
     },
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Stmt {
-    VariableDeclaration { name: String, value: Expr },
+    /// `span` is the variable name, where type errors of the declaration are reported.
+    /// Without an initializer the variable starts at the zero value of its type.
+    VariableDeclaration {
+        name: String,
+        mutable: bool,
+        data_type: Option<DataType>,
+        value: Option<Expr>,
+        span: Span,
+    },
+    Assignment {
+        name: String,
+        value: Expr,
+        span: Span,
+    },
     FunctionDeclaration { 
         name: String, 
         params: Vec<(String, DataType)>, 
-        body: Vec<Stmt> 
+        return_type: Option<DataType>,
+        body: Vec<Stmt>,
+        span: Span,
     },
     Expression(Expr),
     StructDeclaration {
         name: String,
         fields: Vec<(String, DataType)>,
+        span: Span,
     },
     If {
         condition: Expr,
@@ -50,31 +106,65 @@ pub enum Stmt {
         condition: Expr,
         body: Vec<Stmt>,
     },
+    /// `span` is the `return` keyword.
     Return {
         value: Option<Expr>,
+        span: Span,
+    },
+    /// `object.member = value`; `span` is the last `.`, like `Expr::MemberAccess`.
+    FieldAssignment {
+        object: Expr,
+        member: String,
+        value: Expr,
+        span: Span,
     },
 }
 
+/// Syntax error: the parser stops at the first one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub message: String,
+    pub span: Span,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Syntax error at {}: {}", self.span, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
 pub struct AstParser {
     tokens: Vec<Token>,
     current: usize,
+    marks: Vec<usize>,
 }
 
 impl AstParser {
     pub fn new(tokens: Vec<Token>) -> Self {
         info!("AstParser initialized.");
-        AstParser { tokens, current: 0 }
+        AstParser { tokens, current: 0, marks: Vec::new() }
     }
 
-    pub fn parse(&mut self) -> Vec<Stmt> {
+    pub fn parse(&mut self) -> Result<Vec<Stmt>, ParseError> {
         let mut statements = Vec::new();
         while !self.is_at_end() {
-            statements.push(self.declaration());
+            statements.push(self.declaration()?);
         }
-        statements
+        Ok(statements)
+    }
+
+    /// Like `parse`, but also returns the token index where every statement
+    /// starts and every block closes, in source order (a statement before its
+    /// nested blocks). The formatter uses them to put comments back in place.
+    pub fn parse_marked(&mut self) -> Result<(Vec<Stmt>, Vec<usize>), ParseError> {
+        let statements = self.parse()?;
+        Ok((statements, std::mem::take(&mut self.marks)))
     }
 
-    fn declaration(&mut self) -> Stmt {
+    fn declaration(&mut self) -> Result<Stmt, ParseError> {
+        self.marks.push(self.current);
         if self.match_id(std_ids::LET) { return self.var_declaration(); }
         if self.match_id(std_ids::FUNCTION) { return self.function_declaration(); }
         if self.match_id(std_ids::STRUCT) || self.match_id(std_ids::CLASS) {
@@ -83,78 +173,78 @@ impl AstParser {
         if self.match_id(std_ids::IF) { return self.if_statement(); }
         if self.match_id(std_ids::WHILE) { return self.while_statement(); }
         if self.match_id(std_ids::RETURN) { return self.return_statement(); }
+        if self.check_id(std_ids::IDENTIFIER) && self.check_next_id(std_ids::ASSIGN) {
+            return self.assignment();
+        }
         self.statement()
     }
 
-    fn if_statement(&mut self) -> Stmt {
-        self.consume_id(std_ids::L_PAREN, "Expect '(' after 'if'.");
-        let condition = self.expression();
-        self.consume_id(std_ids::R_PAREN, "Expect ')' after if condition.");
+    fn if_statement(&mut self) -> Result<Stmt, ParseError> {
+        self.consume_id(std_ids::L_PAREN, "Expect '(' after 'if'.")?;
+        let condition = self.expression()?;
+        self.consume_id(std_ids::R_PAREN, "Expect ')' after if condition.")?;
 
-        self.consume_id(std_ids::L_BRACE, "Expect '{' before if body.");
-        let then_branch = self.block();
+        self.consume_id(std_ids::L_BRACE, "Expect '{' before if body.")?;
+        let then_branch = self.block()?;
 
         let else_branch = if self.match_id(std_ids::ELSE) {
-            self.consume_id(std_ids::L_BRACE, "Expect '{' after 'else'.");
-            Some(self.block())
+            self.consume_id(std_ids::L_BRACE, "Expect '{' after 'else'.")?;
+            Some(self.block()?)
         } else {
             None
         };
 
-        Stmt::If { condition, then_branch, else_branch }
+        Ok(Stmt::If { condition, then_branch, else_branch })
     }
 
-    fn while_statement(&mut self) -> Stmt {
-        self.consume_id(std_ids::L_PAREN, "Expect '(' after 'while'.");
-        let condition = self.expression();
-        self.consume_id(std_ids::R_PAREN, "Expect ')' after while condition.");
+    fn while_statement(&mut self) -> Result<Stmt, ParseError> {
+        self.consume_id(std_ids::L_PAREN, "Expect '(' after 'while'.")?;
+        let condition = self.expression()?;
+        self.consume_id(std_ids::R_PAREN, "Expect ')' after while condition.")?;
 
-        self.consume_id(std_ids::L_BRACE, "Expect '{' before while body.");
-        let body = self.block();
+        self.consume_id(std_ids::L_BRACE, "Expect '{' before while body.")?;
+        let body = self.block()?;
 
-        Stmt::While { condition, body }
+        Ok(Stmt::While { condition, body })
     }
 
-    fn return_statement(&mut self) -> Stmt {
+    fn return_statement(&mut self) -> Result<Stmt, ParseError> {
+        let span = self.previous().span;
         let value = if !self.check_id(std_ids::SEMICOLON) {
-            Some(self.expression())
+            Some(self.expression()?)
         } else {
             None
         };
-        Stmt::Return { value }
+        Ok(Stmt::Return { value, span })
     }
 
-    fn parse_type(&mut self) -> DataType {
-        let token = self.peek();
-        
-        let data_type = match token.std_token_id {
-            std_ids::INT_TYPE => DataType::Int,
-            std_ids::FLOAT_TYPE => DataType::Float,
-            std_ids::STRING_TYPE => DataType::String,
-            std_ids::BOOL_TYPE => DataType::Bool,
-            std_ids::IDENTIFIER => DataType::Custom(token.value.clone()),
-            _ => {
-                let err = format!("Expected Type, got: {} (ID: {})", token.value, token.std_token_id);
-                error!("{}", err);
-                panic!("{}", err);
-            }
+    fn parse_type(&mut self) -> Result<DataType, ParseError> {
+        let Some(token) = self.tokens.get(self.current) else {
+            return Err(self.error("Expected Type."));
+        };
+
+        let data_type = match DataType::from_std_id(token.std_token_id) {
+            Some(data_type) => data_type,
+            None if token.std_token_id == std_ids::IDENTIFIER => DataType::Custom(token.value.clone()),
+            None => return Err(self.error(&format!("Expected Type, got: {} (ID: {})", token.value, token.std_token_id))),
         };
 
         self.advance();
-        data_type
+        Ok(data_type)
     }
 
-    fn struct_declaration(&mut self) -> Stmt {
-        let name = self.consume_id(std_ids::IDENTIFIER, "Expect struct name").value.clone();
-        self.consume_id(std_ids::L_BRACE, "Expect '{'");
+    fn struct_declaration(&mut self) -> Result<Stmt, ParseError> {
+        let token = self.consume_id(std_ids::IDENTIFIER, "Expect struct name")?;
+        let (name, span) = (token.value.clone(), token.span);
+        self.consume_id(std_ids::L_BRACE, "Expect '{'")?;
 
         let mut fields: Vec<(String, DataType)> = Vec::new();
 
         while !self.check_id(std_ids::R_BRACE) && !self.is_at_end() {
-            let field_name = self.consume_id(std_ids::IDENTIFIER, "Expect field name").value.clone();
-            self.consume_id(std_ids::COLON, "Expect ':'");
+            let field_name = self.consume_id(std_ids::IDENTIFIER, "Expect field name")?.value.clone();
+            self.consume_id(std_ids::COLON, "Expect ':'")?;
             
-            let field_type = self.parse_type();
+            let field_type = self.parse_type()?;
             
             trace!("Field: {} -> {:?}", field_name, field_type);
             fields.push((field_name, field_type));
@@ -162,20 +252,21 @@ impl AstParser {
             self.match_id(std_ids::COMMA);
         }
 
-        self.consume_id(std_ids::R_BRACE, "Expect '}'");
-        Stmt::StructDeclaration { name, fields }
+        self.consume_id(std_ids::R_BRACE, "Expect '}'")?;
+        Ok(Stmt::StructDeclaration { name, fields, span })
     }
 
-    fn function_declaration(&mut self) -> Stmt {
-        let name = self.consume_id(std_ids::IDENTIFIER, "Expect function name").value.clone();
-        self.consume_id(std_ids::L_PAREN, "Expect '('");
+    fn function_declaration(&mut self) -> Result<Stmt, ParseError> {
+        let token = self.consume_id(std_ids::IDENTIFIER, "Expect function name")?;
+        let (name, span) = (token.value.clone(), token.span);
+        self.consume_id(std_ids::L_PAREN, "Expect '('")?;
         
         let mut params: Vec<(String, DataType)> = Vec::new();
 
         if !self.check_id(std_ids::R_PAREN) {
             loop {
-                let param_type = self.parse_type();
-                let param_name = self.consume_id(std_ids::IDENTIFIER, "Expect param name").value.clone();
+                let param_type = self.parse_type()?;
+                let param_name = self.consume_id(std_ids::IDENTIFIER, "Expect param name")?.value.clone();
 
                 trace!("Param: {} -> {:?}", param_name, param_type);
                 params.push((param_name, param_type));
@@ -184,138 +275,215 @@ impl AstParser {
             }
         }
 
-        self.consume_id(std_ids::R_PAREN, "Expect ')'");
-        self.consume_id(std_ids::L_BRACE, "Expect '{'");
-        let body = self.block();
+        self.consume_id(std_ids::R_PAREN, "Expect ')'")?;
+
+        let return_type = if self.match_id(std_ids::ARROW) {
+            let return_type = self.parse_type()?;
+            trace!("Return type: {:?}", return_type);
+            Some(return_type)
+        } else {
+            None
+        };
+
+        self.consume_id(std_ids::L_BRACE, "Expect '{'")?;
+        let body = self.block()?;
         
-        Stmt::FunctionDeclaration { name, params, body }
+        Ok(Stmt::FunctionDeclaration { name, params, return_type, body, span })
     }
 
-    fn var_declaration(&mut self) -> Stmt {
-        let name = self.consume_id(std_ids::IDENTIFIER, "Expect variable name").value.clone();
-        let initializer = if self.match_id(std_ids::ASSIGN) { self.expression() } else { Expr::Number(0.0) };
-        Stmt::VariableDeclaration { name, value: initializer }
+    fn var_declaration(&mut self) -> Result<Stmt, ParseError> {
+        let mutable = self.match_id(std_ids::MUT);
+        let token = self.consume_id(std_ids::IDENTIFIER, "Expect variable name")?;
+        let (name, span) = (token.value.clone(), token.span);
+        let data_type = if self.match_id(std_ids::COLON) { Some(self.parse_type()?) } else { None };
+        let initializer = if self.match_id(std_ids::ASSIGN) {
+            Some(self.expression()?)
+        } else if let Some(DataType::Custom(type_name)) = &data_type {
+            return Err(self.error(&format!(
+                "Expect '=': variable '{}' of struct type '{}' needs an initial value", name, type_name
+            )));
+        } else {
+            None
+        };
+        trace!("Var: {} (mut: {}) -> {:?}", name, mutable, data_type);
+        Ok(Stmt::VariableDeclaration { name, mutable, data_type, value: initializer, span })
+    }
+
+    fn assignment(&mut self) -> Result<Stmt, ParseError> {
+        let token = self.advance().clone();
+        self.consume_id(std_ids::ASSIGN, "Expect '='")?;
+        let value = self.expression()?;
+        Ok(Stmt::Assignment { name: token.value, value, span: token.span })
     }
     
-    fn block(&mut self) -> Vec<Stmt> {
+    fn block(&mut self) -> Result<Vec<Stmt>, ParseError> {
         let mut statements = Vec::new();
         while !self.check_id(std_ids::R_BRACE) && !self.is_at_end() {
-            statements.push(self.declaration());
+            statements.push(self.declaration()?);
+        }
+        self.marks.push(self.current);
+        self.consume_id(std_ids::R_BRACE, "Expect '}'")?;
+        Ok(statements)
+    }
+    fn statement(&mut self) -> Result<Stmt, ParseError> {
+        let expr = self.expression()?;
+        if self.check_id(std_ids::ASSIGN)
+            && let Expr::MemberAccess { object, member, span } = expr
+        {
+            self.advance();
+            let value = self.expression()?;
+            return Ok(Stmt::FieldAssignment { object: *object, member, value, span });
         }
-        self.consume_id(std_ids::R_BRACE, "Expect '}'");
-        statements
+        Ok(Stmt::Expression(expr))
     }
-    fn statement(&mut self) -> Stmt { Stmt::Expression(self.expression()) }
-    fn expression(&mut self) -> Expr {
+    fn expression(&mut self) -> Result<Expr, ParseError> {
         self.comparison()
     }
-    fn comparison(&mut self) -> Expr {
-        let mut expr = self.term();
+    fn comparison(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.term()?;
     
         while self.match_ids(&[
             std_ids::LT, std_ids::GT, std_ids::LTE, std_ids::GTE, std_ids::EQ, std_ids::NEQ
         ]) {
             let operator = self.previous().value.clone();
-            let right = self.term();
+            let span = self.previous().span;
+            let right = self.term()?;
             expr = Expr::BinaryOp {
                 left: Box::new(expr),
                 op: operator,
                 right: Box::new(right),
+                span,
             };
         }
-        expr
+        Ok(expr)
     }
-    fn term(&mut self) -> Expr {
-        let mut expr = self.factor();
+    fn term(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.factor()?;
         while self.match_ids(&[std_ids::PLUS, std_ids::MINUS]) {
             let op = self.previous().value.clone();
-            let right = self.factor();
-            expr = Expr::BinaryOp { left: Box::new(expr), op, right: Box::new(right) };
+            let span = self.previous().span;
+            let right = self.factor()?;
+            expr = Expr::BinaryOp { left: Box::new(expr), op, right: Box::new(right), span };
         }
-        expr
+        Ok(expr)
     }
-    fn factor(&mut self) -> Expr {
-        let mut expr = self.primary();
+    fn factor(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.primary()?;
         while self.match_ids(&[std_ids::MULTIPLY, std_ids::DIVIDE]) {
             let op = self.previous().value.clone();
-            let right = self.primary();
-            expr = Expr::BinaryOp { left: Box::new(expr), op, right: Box::new(right) };
+            let span = self.previous().span;
+            let right = self.primary()?;
+            expr = Expr::BinaryOp { left: Box::new(expr), op, right: Box::new(right), span };
         }
-        expr
+        Ok(expr)
     }
-    fn primary(&mut self) -> Expr {
+    fn primary(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.atom()?;
+        loop {
+            if self.match_id(std_ids::DOT) { // ID 10
+                let span = self.previous().span;
+                let member_name = self.consume_id(std_ids::IDENTIFIER, "Expect field name")?.value.clone();
+                expr = Expr::MemberAccess {
+                    object: Box::new(expr),
+                    member: member_name,
+                    span,
+                };
+            } else if self.match_id(std_ids::L_BRACKET) {
+                let span = self.previous().span;
+                let index = self.expression()?;
+                self.consume_id(std_ids::R_BRACKET, "Expect ']' after index")?;
+                expr = Expr::Index {
+                    object: Box::new(expr),
+                    index: Box::new(index),
+                    span,
+                };
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+    fn atom(&mut self) -> Result<Expr, ParseError> {
         if self.match_id(std_ids::L_PAREN) {
-            let expr = self.expression();
-            self.consume_id(std_ids::R_PAREN, "Expect ')'");
-            return expr;
+            let expr = self.expression()?;
+            self.consume_id(std_ids::R_PAREN, "Expect ')'")?;
+            return Ok(expr);
+        }
+        if self.match_id(std_ids::L_BRACKET) {
+            let span = self.previous().span;
+            let mut items = Vec::new();
+            if !self.check_id(std_ids::R_BRACKET) {
+                loop {
+                    items.push(self.expression()?);
+                    if !self.match_id(std_ids::COMMA) {
+                        break;
+                    }
+                }
+            }
+            self.consume_id(std_ids::R_BRACKET, "Expect ']' after list items")?;
+            return Ok(Expr::List { items, span });
         }
 
-        let token = self.peek().clone();
+        let Some(token) = self.tokens.get(self.current).cloned() else {
+            return Err(self.error("Expect expression."));
+        };
 
         if token.std_token_id == std_ids::INT_LITERAL || token.std_token_id == std_ids::FLOAT_LITERAL {
             self.advance();
-            return Expr::Number(token.value.parse().unwrap_or(0.0));
+            return Ok(Expr::Number(token.value.parse().unwrap_or(0.0)));
         }
         if token.std_token_id == std_ids::STRING_LITERAL {
             self.advance();
-            return Expr::StringLiteral(token.lexem);
+            return Ok(Expr::StringLiteral(token.lexem));
         }
 
         let is_identifier_like = 
             token.std_token_id == std_ids::IDENTIFIER || 
-            token.std_token_id == std_ids::PRINT ||      
-            token.std_token_id == std_ids::INPUT ||      
-            token.std_token_id == std_ids::LEN;
+            std_ids::is_native(token.std_token_id);
 
         if is_identifier_like {
             self.advance();
 
             if self.match_id(std_ids::L_PAREN) {
-                return self.finish_call(token.std_token_id, token.value);
+                return self.finish_call(token.std_token_id, token.value, token.span);
             }
-
-            let mut expr = Expr::Identifier(token.value);
-
-            while self.match_id(std_ids::DOT) { // ID 10
-                let member_name = self.consume_id(std_ids::IDENTIFIER, "Expect field name").value.clone();
-                
-                expr = Expr::MemberAccess { 
-                    object: Box::new(expr),
-                    member: member_name 
-                };
-            }
-
-            return expr;
+            return Ok(Expr::Identifier { name: token.value, span: token.span });
         }
 
-        let err = format!("Unexpected token: {} (ID: {})", token.value, token.std_token_id);
-        error!("{}", err);
-        panic!("{}", err);
+        Err(self.error(&format!("Unexpected token: {} (ID: {})", token.value, token.std_token_id)))
     }
 
-    fn finish_call(&mut self, func_id: u32, name: String) -> Expr {
+    fn finish_call(&mut self, func_id: u32, name: String, span: Span) -> Result<Expr, ParseError> {
         let mut args = Vec::new();
         if !self.check_id(std_ids::R_PAREN) {
             loop {
-                args.push(self.expression());
+                args.push(self.expression()?);
                 if !self.match_id(std_ids::COMMA) {
                     break;
                 }
             }
         }
-        self.consume_id(std_ids::R_PAREN, "Expect ')' after args");
-        Expr::Call { func_id, func_name: name, args }
+        self.consume_id(std_ids::R_PAREN, "Expect ')' after args")?;
+        Ok(Expr::Call { func_id, func_name: name, args, span })
     }
     
     fn match_id(&mut self, id: u32) -> bool { if self.check_id(id) { self.advance(); true } else { false } }
     fn match_ids(&mut self, ids: &[u32]) -> bool { for &id in ids { if self.check_id(id) { self.advance(); return true; } } false }
     fn check_id(&self, id: u32) -> bool { !self.is_at_end() && self.peek().std_token_id == id }
+    fn check_next_id(&self, id: u32) -> bool { self.tokens.get(self.current + 1).is_some_and(|t| t.std_token_id == id) }
     fn advance(&mut self) -> &Token { if !self.is_at_end() { self.current += 1; } self.previous() }
     fn is_at_end(&self) -> bool { self.current >= self.tokens.len() }
     fn peek(&self) -> &Token { &self.tokens[self.current] }
     fn previous(&self) -> &Token { &self.tokens[self.current - 1] }
-    fn consume_id(&mut self, id: u32, msg: &str) -> &Token {
-        if self.check_id(id) { return self.advance(); }
-        panic!("{} Expected ID: {}, Got: {}", msg, id, self.peek().value);
+    fn consume_id(&mut self, id: u32, msg: &str) -> Result<&Token, ParseError> {
+        if self.check_id(id) { return Ok(self.advance()); }
+        let got = self.tokens.get(self.current).map_or("end of input", |t| t.value.as_str());
+        Err(self.error(&format!("{} Expected ID: {}, Got: {}", msg, id, got)))
+    }
+
+    /// Error at the current token, or at the last one when the input ran out.
+    fn error(&self, message: &str) -> ParseError {
+        error!("{}", message);
+        let span = self.tokens.get(self.current).or(self.tokens.last()).map(|t| t.span).unwrap_or_default();
+        ParseError { message: message.to_string(), span }
     }
 }
\ No newline at end of file
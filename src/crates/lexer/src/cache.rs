@@ -0,0 +1,128 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use db::model::{AllLexem, GlossaryEntry, StdLexeme};
+use tracing::{debug, info, warn};
+use crate::serialize::{self, ParsedProgram};
+
+/// On-disk cache of parsed programs, one entry per source file.
+///
+/// An entry starts with a stamp of the source text, the language and the
+/// lexicon it was tokenized with, followed by the program in binary form
+/// (lexicon rows included); a different stamp is a miss.
+pub struct ProgramCache {
+    dir: PathBuf,
+}
+
+impl ProgramCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        ProgramCache { dir: dir.into() }
+    }
+
+    /// `lexicon` is `DB::select_lexicon_revision`, or a `lexicon_fingerprint`
+    /// where the revision table is missing.
+    pub fn load(&self, path: &Path, source: &str, lang: &str, lexicon: u64) -> Option<ParsedProgram> {
+        let entry = self.entry_path(path);
+        let bytes = fs::read(&entry).ok()?;
+        let body = bytes.strip_prefix(stamp(source, lang, lexicon).as_slice())?;
+        match serialize::from_binary(body) {
+            Ok(program) => {
+                info!("Loaded {} from cache {}", path.display(), entry.display());
+                Some(program)
+            }
+            Err(e) => {
+                warn!("Ignoring cache entry {}: {}", entry.display(), e);
+                None
+            }
+        }
+    }
+
+    /// Failures are only logged: a missing cache never stops a run.
+    pub fn store(&self, path: &Path, source: &str, lang: &str, lexicon: u64, program: &ParsedProgram) {
+        let entry = self.entry_path(path);
+        let mut bytes = stamp(source, lang, lexicon);
+        bytes.extend(serialize::to_binary(program));
+        if let Err(e) = fs::create_dir_all(&self.dir).and_then(|_| fs::write(&entry, bytes)) {
+            warn!("Cannot write cache entry {}: {}", entry.display(), e);
+            return;
+        }
+        debug!("Cached {} in {}", path.display(), entry.display());
+    }
+
+    fn entry_path(&self, path: &Path) -> PathBuf {
+        let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let mut hasher = Fnv1a::new();
+        hasher.field(path.as_os_str().as_encoded_bytes());
+        self.dir.join(format!("{:016x}.usqlast", hasher.finish()))
+    }
+}
+
+/// Changes whenever a lexicon row (or the standard lexeme type behind it) or
+/// a glossary entry is added, removed or edited.
+pub fn lexicon_fingerprint(rows: &[(AllLexem, StdLexeme)], glossary: &[GlossaryEntry]) -> u64 {
+    let mut hasher = Fnv1a::new();
+    for (lexem, std_lexem) in rows {
+        hasher.number(lexem.id);
+        hasher.field(lexem.lang_name.as_bytes());
+        hasher.field(lexem.lexem.as_bytes());
+        match &lexem.type_info {
+            Some(type_info) => {
+                hasher.number(1);
+                hasher.field(type_info.as_bytes());
+            }
+            None => hasher.number(0),
+        }
+        hasher.number(lexem.std_lexem);
+        hasher.number(std_lexem.id);
+        hasher.field(std_lexem.name.as_bytes());
+        hasher.field(std_lexem.lexem_type.as_bytes());
+    }
+    // Рядки лексикону й глосарію не зливаються: між ними стоїть кількість записів
+    hasher.number(glossary.len() as u32);
+    for entry in glossary {
+        hasher.number(entry.id);
+        hasher.field(entry.lang_name.as_bytes());
+        hasher.field(entry.identifier.as_bytes());
+        hasher.field(entry.canonical.as_bytes());
+    }
+    hasher.finish()
+}
+
+fn stamp(source: &str, lang: &str, lexicon: u64) -> Vec<u8> {
+    let mut hasher = Fnv1a::new();
+    hasher.field(source.as_bytes());
+    hasher.field(lang.as_bytes());
+    let mut bytes = hasher.finish().to_le_bytes().to_vec();
+    bytes.extend(lexicon.to_le_bytes());
+    bytes
+}
+
+/// 64-bit FNV-1a. Unlike `DefaultHasher`, its output is fixed across Rust
+/// releases and platforms, so cache entries stay valid between builds.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn number(&mut self, n: u32) {
+        self.write(&n.to_le_bytes());
+    }
+
+    /// Length-prefixed, so neighbouring fields cannot run into each other.
+    fn field(&mut self, bytes: &[u8]) {
+        self.write(&(bytes.len() as u64).to_le_bytes());
+        self.write(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
@@ -0,0 +1,312 @@
+use std::collections::{HashMap, VecDeque};
+use tracing::{info, warn};
+use crate::ast::{AstParser, DataType, Expr, ParseError, Stmt};
+use crate::tokenizer::{Token, std_ids};
+use crate::translate::Translator;
+
+const INDENT: &str = "    ";
+
+/// Comment lifted out of the lossless token stream.
+struct Comment {
+    text: String,
+    /// Index of the code token that follows the comment.
+    before: usize,
+    /// Written on the same line as the code before it.
+    trailing: bool,
+    blank_line_before: bool,
+}
+
+/// Prints a lossless token stream (`Parser::parse_lossless`) in canonical
+/// form: one statement per line, four-space indentation, single spaces
+/// around operators, keywords spelled in the translator's language.
+///
+/// Comments are kept next to the statement they precede, or at the end of
+/// the line they trailed; blank lines between statements collapse to one.
+/// A string literal holding both quote characters is an error, as the
+/// language has no escapes to print it with.
+pub fn format(tokens: &[Token], translator: &Translator) -> Result<String, ParseError> {
+    let mut code = Vec::new();
+    let mut blank_before = Vec::new();
+    let mut comments = VecDeque::new();
+    let mut newlines = 0;
+
+    for token in tokens {
+        match token.token_type.as_str() {
+            "Whitespace" => newlines += token.value.matches('\n').count(),
+            "Comment" => {
+                comments.push_back(Comment {
+                    text: token.value.trim_end().to_string(),
+                    before: code.len(),
+                    trailing: !code.is_empty() && newlines == 0,
+                    blank_line_before: newlines >= 2,
+                });
+                newlines = 0;
+            }
+            _ => {
+                // Лексер не знає екранування, тож такий рядок не записати
+                if token.std_token_id == std_ids::STRING_LITERAL && token.lexem.contains('\'') && token.lexem.contains('"') {
+                    return Err(ParseError {
+                        message: "String literal contains both ' and \", which cannot be written back".to_string(),
+                        span: token.span,
+                    });
+                }
+                blank_before.push(newlines >= 2);
+                code.push(token.clone());
+                newlines = 0;
+            }
+        }
+    }
+
+    // Ключові слова пишемо так, як вони вже написані у файлі (`функція`, а не `Функція`)
+    let mut source_spellings = HashMap::new();
+    for token in code.iter().filter(|t| t.lang.as_deref() == Some(translator.lang())) {
+        source_spellings.entry(token.std_token_id).or_insert_with(|| token.value.clone());
+    }
+
+    let (statements, marks) = AstParser::new(code).parse_marked()?;
+
+    let mut printer = Printer {
+        translator,
+        source_spellings,
+        comments,
+        blank_before,
+        marks: marks.into(),
+        lines: Vec::new(),
+        depth: 0,
+    };
+    for stmt in &statements {
+        printer.statement(stmt);
+    }
+    printer.flush_comments(usize::MAX);
+
+    info!("Formatted {} statements into {} lines", statements.len(), printer.lines.len());
+    let mut output = printer.lines.join("\n");
+    output.push('\n');
+    Ok(output)
+}
+
+struct Printer<'a> {
+    translator: &'a Translator,
+    source_spellings: HashMap<u32, String>,
+    comments: VecDeque<Comment>,
+    blank_before: Vec<bool>,
+    marks: VecDeque<usize>,
+    lines: Vec<String>,
+    depth: usize,
+}
+
+impl Printer<'_> {
+    fn line(&mut self, text: &str) {
+        self.lines.push(format!("{}{}", INDENT.repeat(self.depth), text));
+    }
+
+    /// Blank line, unless at the start of the file or of a block.
+    fn blank_line(&mut self) {
+        if let Some(last) = self.lines.last() && !last.is_empty() && !last.ends_with('{') {
+            self.lines.push(String::new());
+        }
+    }
+
+    /// Emits every comment written before the code token `upto`.
+    fn flush_comments(&mut self, upto: usize) {
+        while self.comments.front().is_some_and(|c| c.before <= upto) {
+            let comment = self.comments.pop_front().unwrap();
+            if comment.trailing && let Some(last) = self.lines.last_mut() && !last.is_empty() {
+                last.push(' ');
+                last.push_str(&comment.text);
+                continue;
+            }
+            if comment.blank_line_before {
+                self.blank_line();
+            }
+            self.line(&comment.text);
+        }
+    }
+
+    fn next_mark(&mut self) -> usize {
+        self.marks.pop_front().expect("AST marks out of sync with statements")
+    }
+
+    fn statement(&mut self, stmt: &Stmt) {
+        let start = self.next_mark();
+        self.flush_comments(start);
+        if self.blank_before.get(start).copied().unwrap_or(false) {
+            self.blank_line();
+        }
+
+        match stmt {
+            Stmt::VariableDeclaration { name, mutable, data_type, value, .. } => {
+                let mut text = self.keyword(std_ids::LET, "let");
+                if *mutable {
+                    text = format!("{} {}", text, self.keyword(std_ids::MUT, "mut"));
+                }
+                text = format!("{} {}", text, self.translator.identifier(name));
+                if let Some(data_type) = data_type {
+                    text = format!("{}: {}", text, self.data_type(data_type));
+                }
+                if let Some(value) = value {
+                    text = format!("{} = {}", text, self.expr(value));
+                }
+                self.line(&text);
+            }
+            Stmt::Assignment { name, value, .. } => {
+                let text = format!("{} = {}", self.translator.identifier(name), self.expr(value));
+                self.line(&text);
+            }
+            Stmt::FieldAssignment { object, member, value, .. } => {
+                let text = format!("{}.{} = {}", self.expr(object), self.translator.identifier(member), self.expr(value));
+                self.line(&text);
+            }
+            Stmt::FunctionDeclaration { name, params, return_type, body, .. } => {
+                let params: Vec<String> = params.iter()
+                    .map(|(param, data_type)| format!("{} {}", self.data_type(data_type), self.translator.identifier(param)))
+                    .collect();
+                let mut text = format!(
+                    "{} {}({})",
+                    self.keyword(std_ids::FUNCTION, "function"),
+                    self.translator.identifier(name),
+                    params.join(", ")
+                );
+                if let Some(return_type) = return_type {
+                    text = format!("{} {} {}", text, self.keyword(std_ids::ARROW, "->"), self.data_type(return_type));
+                }
+                self.line(&format!("{} {{", text));
+                self.block(body);
+                self.line("}");
+            }
+            Stmt::StructDeclaration { name, fields, .. } => {
+                let text = format!("{} {} {{", self.keyword(std_ids::STRUCT, "struct"), self.translator.identifier(name));
+                self.line(&text);
+                self.depth += 1;
+                for (field, data_type) in fields {
+                    let text = format!("{}: {},", self.translator.identifier(field), self.data_type(data_type));
+                    self.line(&text);
+                }
+                self.depth -= 1;
+                self.line("}");
+            }
+            Stmt::If { condition, then_branch, else_branch } => {
+                let text = format!("{} ({}) {{", self.keyword(std_ids::IF, "if"), self.expr(condition));
+                self.line(&text);
+                self.block(then_branch);
+                match else_branch {
+                    Some(else_branch) => {
+                        let text = format!("}} {} {{", self.keyword(std_ids::ELSE, "else"));
+                        self.line(&text);
+                        self.block(else_branch);
+                        self.line("}");
+                    }
+                    None => self.line("}"),
+                }
+            }
+            Stmt::While { condition, body } => {
+                let text = format!("{} ({}) {{", self.keyword(std_ids::WHILE, "while"), self.expr(condition));
+                self.line(&text);
+                self.block(body);
+                self.line("}");
+            }
+            Stmt::Return { value, .. } => {
+                let keyword = self.keyword(std_ids::RETURN, "return");
+                let text = match value {
+                    Some(value) => format!("{} {}", keyword, self.expr(value)),
+                    None => keyword,
+                };
+                self.line(&text);
+            }
+            Stmt::Expression(expr) => {
+                let text = self.expr(expr);
+                self.line(&text);
+            }
+        }
+    }
+
+    /// Body of a block; the caller prints the braces.
+    fn block(&mut self, statements: &[Stmt]) {
+        self.depth += 1;
+        for stmt in statements {
+            self.statement(stmt);
+        }
+        let close = self.next_mark();
+        self.flush_comments(close);
+        self.depth -= 1;
+    }
+
+    fn expr(&self, expr: &Expr) -> String {
+        match expr {
+            Expr::Number(n) => n.to_string(),
+            Expr::StringLiteral(s) if s.contains('"') => format!("'{}'", s),
+            Expr::StringLiteral(s) => format!("\"{}\"", s),
+            Expr::Identifier { name, .. } => self.translator.identifier(name).to_string(),
+            Expr::BinaryOp { left, op, right, .. } => {
+                let precedence = precedence(op);
+                format!("{} {} {}", self.operand(left, precedence, false), op, self.operand(right, precedence, true))
+            }
+            Expr::Call { func_id, func_name, args, .. } => {
+                let name = if std_ids::is_native(*func_id) {
+                    self.keyword(*func_id, func_name)
+                } else {
+                    self.translator.identifier(func_name).to_string()
+                };
+                let args: Vec<String> = args.iter().map(|arg| self.expr(arg)).collect();
+                format!("{}({})", name, args.join(", "))
+            }
+            Expr::MemberAccess { object, member, .. } => {
+                format!("{}.{}", self.operand(object, POSTFIX, false), self.translator.identifier(member))
+            }
+            Expr::List { items, .. } => {
+                let items: Vec<String> = items.iter().map(|item| self.expr(item)).collect();
+                format!("[{}]", items.join(", "))
+            }
+            Expr::Index { object, index, .. } => {
+                format!("{}[{}]", self.operand(object, POSTFIX, false), self.expr(index))
+            }
+        }
+    }
+
+    /// Operand of a binary operator, parenthesized only where the parser
+    /// would otherwise group it differently (all operators are left-associative).
+    fn operand(&self, expr: &Expr, parent: u8, right: bool) -> String {
+        if let Expr::BinaryOp { op, .. } = expr {
+            let inner = precedence(op);
+            if inner < parent || (right && inner == parent) {
+                return format!("({})", self.expr(expr));
+            }
+        }
+        self.expr(expr)
+    }
+
+    fn data_type(&self, data_type: &DataType) -> String {
+        match data_type {
+            DataType::Int => self.keyword(std_ids::INT_TYPE, "int"),
+            DataType::Float => self.keyword(std_ids::FLOAT_TYPE, "float"),
+            DataType::String => self.keyword(std_ids::STRING_TYPE, "string"),
+            DataType::Bool => self.keyword(std_ids::BOOL_TYPE, "bool"),
+            DataType::List => self.keyword(std_ids::LIST_TYPE, "list"),
+            DataType::Custom(name) => self.translator.identifier(name).to_string(),
+        }
+    }
+
+    fn keyword(&self, std_id: u32, fallback: &str) -> String {
+        if let Some(spelling) = self.source_spellings.get(&std_id) {
+            return spelling.clone();
+        }
+        match self.translator.spelling(std_id) {
+            Some(spelling) => spelling.to_string(),
+            None => {
+                warn!("No spelling for ID {} in '{}', using '{}'", std_id, self.translator.lang(), fallback);
+                fallback.to_string()
+            }
+        }
+    }
+}
+
+/// Binds tighter than any binary operator: `.member` and `[index]`.
+const POSTFIX: u8 = 4;
+
+fn precedence(op: &str) -> u8 {
+    match op {
+        "*" | "/" => 3,
+        "+" | "-" => 2,
+        _ => 1,
+    }
+}
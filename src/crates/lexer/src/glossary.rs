@@ -0,0 +1,37 @@
+use std::collections::HashMap;
+use db::model::GlossaryEntry;
+
+/// Cross-language aliases of user identifiers (`Тварина` ↔ `animal`).
+///
+/// Every spelling maps to a canonical name; identifiers without an entry are
+/// their own canonical name.
+#[derive(Debug, Clone, Default)]
+pub struct Glossary {
+    canonical: HashMap<String, String>,
+    spellings: HashMap<(String, String), String>,
+}
+
+impl Glossary {
+    pub fn new(entries: &[GlossaryEntry]) -> Self {
+        let mut glossary = Glossary::default();
+        for entry in entries {
+            glossary.canonical.insert(entry.identifier.clone(), entry.canonical.clone());
+            glossary.spellings.insert((entry.lang_name.clone(), entry.canonical.clone()), entry.identifier.clone());
+        }
+        glossary
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.canonical.is_empty()
+    }
+
+    pub fn canonical<'a>(&'a self, identifier: &'a str) -> &'a str {
+        self.canonical.get(identifier).map(String::as_str).unwrap_or(identifier)
+    }
+
+    /// Spelling of `identifier` in `lang`, if the glossary knows one.
+    pub fn spelling(&self, identifier: &str, lang: &str) -> Option<&str> {
+        let key = (lang.to_string(), self.canonical(identifier).to_string());
+        self.spellings.get(&key).map(String::as_str)
+    }
+}
@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+use db::db::DB;
+use db::model::{AllLexem, StdLexeme};
+
+/// Where the tokenizer looks words up: the MySQL lexicon, or rows held in
+/// memory by an application that embeds the interpreter.
+pub trait Lexicon {
+    /// Rows spelled exactly `lexem`, in any language, with their standard lexeme.
+    fn lookup(&self, lexem: &str) -> Vec<(AllLexem, StdLexeme)>;
+}
+
+impl Lexicon for DB {
+    fn lookup(&self, lexem: &str) -> Vec<(AllLexem, StdLexeme)> {
+        self.select_lexem(lexem)
+    }
+}
+
+impl<L: Lexicon + ?Sized> Lexicon for &L {
+    fn lookup(&self, lexem: &str) -> Vec<(AllLexem, StdLexeme)> {
+        (**self).lookup(lexem)
+    }
+}
+
+impl<L: Lexicon + ?Sized> Lexicon for Box<L> {
+    fn lookup(&self, lexem: &str) -> Vec<(AllLexem, StdLexeme)> {
+        (**self).lookup(lexem)
+    }
+}
+
+/// Lexicon rows kept in memory, e.g. `DB::select_lexicon` loaded once.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryLexicon {
+    rows: HashMap<String, Vec<(AllLexem, StdLexeme)>>,
+}
+
+impl MemoryLexicon {
+    pub fn new(rows: Vec<(AllLexem, StdLexeme)>) -> Self {
+        let mut lexicon = MemoryLexicon::default();
+        for row in rows {
+            lexicon.rows.entry(row.0.lexem.clone()).or_default().push(row);
+        }
+        lexicon
+    }
+
+    /// Adds the spelling `lexem` of standard lexeme `std_id` in `lang`.
+    pub fn insert(&mut self, lang: &str, lexem: &str, std_id: u32, lexem_type: &str) {
+        let id = self.rows.values().map(Vec::len).sum::<usize>() as u32 + 1;
+        let row = AllLexem {
+            id,
+            lang_name: lang.to_string(),
+            lexem: lexem.to_string(),
+            type_info: None,
+            std_lexem: std_id,
+        };
+        let std_lexem = StdLexeme { id: std_id, name: lexem.to_string(), lexem_type: lexem_type.to_string() };
+        self.rows.entry(lexem.to_string()).or_default().push((row, std_lexem));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+}
+
+impl Lexicon for MemoryLexicon {
+    fn lookup(&self, lexem: &str) -> Vec<(AllLexem, StdLexeme)> {
+        self.rows.get(lexem).cloned().unwrap_or_default()
+    }
+}
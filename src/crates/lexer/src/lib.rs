@@ -1,2 +1,8 @@
 pub mod tokenizer;
-pub mod ast;
\ No newline at end of file
+pub mod ast;
+pub mod translate;
+pub mod glossary;
+pub mod format;
+pub mod serialize;
+pub mod cache;
+pub mod lexicon;
\ No newline at end of file
@@ -0,0 +1,84 @@
+use std::fmt;
+use db::model::{AllLexem, GlossaryEntry};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+use crate::ast::Stmt;
+
+/// Header of the binary AST form, followed by the format version.
+pub const MAGIC: &[u8; 4] = b"USQL";
+/// Bumped whenever `ParsedProgram` or the `Stmt`/`Expr`/`DataType` in it
+/// change shape, so stale files are rejected.
+pub const FORMAT_VERSION: u8 = 5;
+
+/// A parsed program with the lexicon rows it runs with, so running it needs
+/// nothing else from the database.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct ParsedProgram {
+    pub statements: Vec<Stmt>,
+    pub glossary: Vec<GlossaryEntry>,
+    /// Lexemes of the program's language, for type and date names.
+    pub lexems: Vec<AllLexem>,
+}
+
+#[derive(Debug)]
+pub enum SerializeError {
+    Json(String),
+    Binary(String),
+    /// Binary input without the `USQL` header.
+    NotAnAst,
+    UnsupportedVersion(u8),
+}
+
+impl fmt::Display for SerializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SerializeError::Json(e) => write!(f, "Invalid AST JSON: {}", e),
+            SerializeError::Binary(e) => write!(f, "Invalid binary AST: {}", e),
+            SerializeError::NotAnAst => write!(f, "Not a binary AST file"),
+            SerializeError::UnsupportedVersion(v) => {
+                write!(f, "Binary AST format version {} is not supported (expected {})", v, FORMAT_VERSION)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SerializeError {}
+
+pub fn to_json(program: &ParsedProgram) -> String {
+    serde_json::to_string_pretty(program).expect("AST is always serializable")
+}
+
+pub fn from_json(input: &str) -> Result<ParsedProgram, SerializeError> {
+    serde_json::from_str(input).map_err(|e| SerializeError::Json(e.to_string()))
+}
+
+pub fn to_binary(program: &ParsedProgram) -> Vec<u8> {
+    let mut bytes = MAGIC.to_vec();
+    bytes.push(FORMAT_VERSION);
+    let body = bincode::serde::encode_to_vec(program, bincode::config::standard())
+        .expect("AST is always serializable");
+    bytes.extend(body);
+    debug!("Encoded {} statements into {} bytes", program.statements.len(), bytes.len());
+    bytes
+}
+
+pub fn from_binary(bytes: &[u8]) -> Result<ParsedProgram, SerializeError> {
+    let body = bytes.strip_prefix(MAGIC.as_slice()).ok_or(SerializeError::NotAnAst)?;
+    let (&version, body) = body.split_first().ok_or(SerializeError::NotAnAst)?;
+    if version != FORMAT_VERSION {
+        return Err(SerializeError::UnsupportedVersion(version));
+    }
+    let (program, _) = bincode::serde::decode_from_slice(body, bincode::config::standard())
+        .map_err(|e| SerializeError::Binary(e.to_string()))?;
+    Ok(program)
+}
+
+/// Decodes either form, telling them apart by the binary header.
+pub fn from_bytes(bytes: &[u8]) -> Result<ParsedProgram, SerializeError> {
+    if bytes.starts_with(MAGIC) {
+        from_binary(bytes)
+    } else {
+        let text = std::str::from_utf8(bytes).map_err(|e| SerializeError::Json(e.to_string()))?;
+        from_json(text)
+    }
+}
@@ -1,10 +1,14 @@
 use db::db::DB;
+use crate::lexicon::Lexicon;
 use tracing::info;
+use serde::{Deserialize, Serialize};
 use std::iter::Peekable;
 use std::str::Chars;
+use std::fmt;
 
 pub mod std_ids {
     pub const LET: u32 = 53;
+    pub const MUT: u32 = 54;       // mut / змінний
     pub const FUNCTION: u32 = 46;  // function / функція
     pub const IDENTIFIER: u32 = 70;
     pub const INT_LITERAL: u32 = 71;
@@ -27,6 +31,8 @@ pub mod std_ids {
     pub const R_PAREN: u32 = 4;    // )
     pub const L_BRACE: u32 = 1;    // {
     pub const R_BRACE: u32 = 2;    // }
+    pub const L_BRACKET: u32 = 5;  // [
+    pub const R_BRACKET: u32 = 6;  // ]
     pub const COMMA: u32 = 9;      // ,
 
     pub const CLASS: u32 = 47;     // class / Клас
@@ -37,8 +43,20 @@ pub mod std_ids {
     pub const FLOAT_TYPE: u32 = 72;  // float / дійсне
     pub const STRING_TYPE: u32 = 73; // string / рядок
     pub const BOOL_TYPE: u32 = 75;   // bool / булеве
+    pub const LIST_TYPE: u32 = 76;   // list / список
     pub const DOT: u32 = 10;        // .
+    pub const ARROW: u32 = 11;      // ->
 
+    /// Month (`January` .. `December`) and weekday (`Monday` .. `Sunday`)
+    /// names, consecutive from these IDs. Their lexem type is `DATE_NAME`.
+    pub const JANUARY: u32 = 100;
+    pub const MONDAY: u32 = 112;
+    /// Lexem type of names that only localize output: in source they are
+    /// plain identifiers.
+    pub const DATE_NAME: &str = "DateName";
+
+    /// Native functions (`NativeFunc` in `StdLexemeTBL`) take IDs from here up.
+    pub const NATIVE_BASE: u32 = 300;
     pub const PRINT: u32 = 300;
     pub const INPUT: u32 = 301;
     pub const LEN: u32 = 302;
@@ -49,49 +67,146 @@ pub mod std_ids {
     pub const LTE: u32 = 21;  // <=
     pub const GT: u32 = 22;   // >
     pub const GTE: u32 = 23;  // >=
+
+    pub fn is_native(id: u32) -> bool {
+        id >= NATIVE_BASE
+    }
 }
 
 
-#[derive(Debug, Clone)]
+/// Position of a token in the source text, both 1-based.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct Span {
+    pub line: u32,
+    pub column: u32,
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct Token {
     pub value: String,
     pub token_type: String,
     pub std_token_id: u32,
     pub lexem: String,
+    pub span: Span,
+    /// Language of the lexicon row the token matched, `None` for literals,
+    /// unknown identifiers and trivia.
+    pub lang: Option<String>,
+}
+
+impl Token {
+    /// Whitespace and comments, only produced by `Parser::parse_lossless`.
+    pub fn is_trivia(&self) -> bool {
+        self.token_type == "Whitespace" || self.token_type == "Comment"
+    }
+
+    fn trivia(token_type: &str, value: String, span: Span) -> Token {
+        Token {
+            value: value.clone(),
+            token_type: token_type.to_string(),
+            std_token_id: 0,
+            lexem: value,
+            span,
+            lang: None,
+        }
+    }
+}
+
+/// Character stream that keeps track of the current line and column.
+struct Cursor<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: u32,
+    column: u32,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(input: &'a str) -> Self {
+        Cursor { chars: input.chars().peekable(), line: 1, column: 1 }
+    }
+
+    fn peek(&mut self) -> Option<&char> {
+        self.chars.peek()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn span(&self) -> Span {
+        Span { line: self.line, column: self.column }
+    }
 }
 
-pub struct Parser {
-    db: DB,
+pub struct Parser<L: Lexicon = DB> {
+    lexicon: L,
 }
 
-impl Parser {
-    pub fn new(db: DB) -> Self {
+impl<L: Lexicon> Parser<L> {
+    pub fn new(lexicon: L) -> Self {
         info!("Parser initialized.");
-        Parser { db }
+        Parser { lexicon }
     }
 
     pub fn parse(&mut self, input: &str) -> Vec<Token> {
+        self.parse_lossless(input).into_iter().filter(|t| !t.is_trivia()).collect()
+    }
+
+    /// Tokenizes keeping whitespace and comments, so that concatenating the
+    /// `value` of every token gives back the original source.
+    pub fn parse_lossless(&mut self, input: &str) -> Vec<Token> {
         let mut tokens = Vec::new();
-        let mut chars = input.chars().peekable();
+        let mut chars = Cursor::new(input);
 
         while let Some(&c) = chars.peek() {
+            let span = chars.span();
             match c {
-                c if c.is_whitespace() => { chars.next(); }
+                c if c.is_whitespace() => {
+                    let mut whitespace = String::new();
+                    while let Some(&ws) = chars.peek() {
+                        if !ws.is_whitespace() { break; }
+                        whitespace.push(ws);
+                        chars.next();
+                    }
+                    tokens.push(Token::trivia("Whitespace", whitespace, span));
+                }
                 
                 '/' => {
                     chars.next();
-                    if let Some(&next) = chars.peek() {
-                        if next == '/' {
-                            while let Some(&comment_char) = chars.peek() {
-                                if comment_char == '\n' {
-                                    break;
-                                }
-                                chars.next();
+                    if let Some(&'/') = chars.peek() {
+                        let mut comment = String::from("/");
+                        while let Some(&comment_char) = chars.peek() {
+                            if comment_char == '\n' {
+                                break;
                             }
-                            continue;
+                            comment.push(comment_char);
+                            chars.next();
                         }
+                        tokens.push(Token::trivia("Comment", comment, span));
+                        continue;
+                    }
+                    tokens.push(self.create_token_from_word("/", span));
+                }
+
+                '-' => {
+                    chars.next();
+                    if let Some(&'>') = chars.peek() {
+                        chars.next();
+                        tokens.push(self.create_token_from_word("->", span));
+                    } else {
+                        tokens.push(self.create_token_from_word("-", span));
                     }
-                    tokens.push(self.create_token_from_word("/"));
                 }
 
                 '\'' | '"' => {
@@ -99,18 +214,19 @@ impl Parser {
                 }
                 c if !is_separator(c) => {
                     let word = self.read_word(&mut chars);
-                    tokens.push(self.create_token_from_word(&word));
+                    tokens.push(self.create_token_from_word(&word, span));
                 }
                 _ => {
                     let char_str = chars.next().unwrap().to_string();
-                    tokens.push(self.create_token_from_word(&char_str));
+                    tokens.push(self.create_token_from_word(&char_str, span));
                 }
             }
         }
         tokens
     }
 
-    fn read_string(&self, chars: &mut Peekable<Chars>, quote_type: char) -> Token {
+    fn read_string(&self, chars: &mut Cursor, quote_type: char) -> Token {
+        let span = chars.span();
         let mut value = String::new();
         chars.next(); 
         value.push(quote_type);
@@ -126,20 +242,25 @@ impl Parser {
                     token_type: "Literal".to_string(),
                     std_token_id: std_ids::STRING_LITERAL, // ID 73
                     lexem: content,
+                    span,
+                    lang: None,
                 };
             }
             content.push(chars.next().unwrap());
         }
         
+        value.push_str(&content);
         Token {
-            value: content.clone(),
+            value,
             token_type: "Error".to_string(),
             std_token_id: 0, 
             lexem: content,
+            span,
+            lang: None,
         }
     }
 
-    fn read_word(&self, chars: &mut Peekable<Chars>) -> String {
+    fn read_word(&self, chars: &mut Cursor) -> String {
         let mut word = String::new();
         while let Some(&c) = chars.peek() {
             if is_separator(c) || c.is_whitespace() { break; }
@@ -148,15 +269,17 @@ impl Parser {
         word
     }
 
-    fn create_token_from_word(&self, lexeme_str: &str) -> Token {
-        let db_result = self.db.select_lexem(lexeme_str);
+    fn create_token_from_word(&self, lexeme_str: &str, span: Span) -> Token {
+        let db_result = self.lexicon.lookup(lexeme_str);
 
-        if let Some((all_tbl, std_tbl)) = db_result.first() {
+        if let Some((all_tbl, std_tbl)) = db_result.iter().find(|(_, std_tbl)| std_tbl.lexem_type != std_ids::DATE_NAME) {
             Token {
                 value: lexeme_str.to_string(),
                 token_type: std_tbl.lexem_type.clone(),
-                std_token_id: all_tbl.std_lexem as u32, // ID 53, 13, 33 etc.
+                std_token_id: all_tbl.std_lexem, // ID 53, 13, 33 etc.
                 lexem: all_tbl.lexem.clone(),
+                span,
+                lang: Some(all_tbl.lang_name.clone()),
             }
         } else {
             let (guessed_type, guessed_id) = self.guess_type_and_id(lexeme_str);
@@ -165,6 +288,8 @@ impl Parser {
                 token_type: guessed_type,
                 std_token_id: guessed_id,
                 lexem: lexeme_str.to_string(),
+                span,
+                lang: None,
             }
         }
     }
@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+use db::model::AllLexem;
+use tracing::{info, warn};
+use crate::glossary::Glossary;
+use crate::tokenizer::{Token, std_ids};
+
+/// Rewrites programs into one target language of the lexicon.
+pub struct Translator {
+    lang: String,
+    spellings: HashMap<u32, String>,
+    glossary: Glossary,
+}
+
+impl Translator {
+    /// `rows` are the lexicon rows of the target language.
+    pub fn new(lang: &str, rows: &[AllLexem], glossary: Glossary) -> Self {
+        Translator {
+            lang: lang.to_string(),
+            spellings: target_spellings(rows),
+            glossary,
+        }
+    }
+
+    pub fn lang(&self) -> &str {
+        &self.lang
+    }
+
+    pub fn has_lexems(&self) -> bool {
+        !self.spellings.is_empty()
+    }
+
+    /// Spelling of a standard lexeme in the target language.
+    pub fn spelling(&self, std_id: u32) -> Option<&str> {
+        self.spellings.get(&std_id).map(String::as_str)
+    }
+
+    /// Spelling of a user identifier in the target language, per the glossary.
+    pub fn identifier<'a>(&'a self, name: &'a str) -> &'a str {
+        self.glossary.spelling(name, &self.lang).unwrap_or(name)
+    }
+
+    /// Rewrites a lossless token stream, replacing every token that matched the
+    /// lexicon with the target language's spelling and every identifier listed
+    /// in the glossary with its alias. Literals, comments and whitespace are
+    /// copied unchanged.
+    pub fn translate(&self, tokens: &[Token]) -> String {
+        let mut output = String::new();
+        let mut missing = 0;
+        for token in tokens {
+            if token.lang.is_none() {
+                if token.std_token_id == std_ids::IDENTIFIER {
+                    output.push_str(self.identifier(&token.value));
+                } else {
+                    output.push_str(&token.value);
+                }
+                continue;
+            }
+            match self.spelling(token.std_token_id) {
+                Some(spelling) => output.push_str(spelling),
+                None => {
+                    warn!("No translation for '{}' (ID: {}) at {}", token.value, token.std_token_id, token.span);
+                    missing += 1;
+                    output.push_str(&token.value);
+                }
+            }
+        }
+        info!("Translated {} tokens ({} without translation)", tokens.len(), missing);
+        output
+    }
+}
+
+/// Spelling of every standard lexeme in the target language, keyed by `std_lexem`.
+///
+/// Some standard IDs have both a placeholder row (`int_literal`) and a real
+/// keyword (`int`, marked with `type_info`); the keyword wins.
+fn target_spellings(rows: &[AllLexem]) -> HashMap<u32, String> {
+    let mut spellings: HashMap<u32, &AllLexem> = HashMap::new();
+    for row in rows {
+        match spellings.get(&row.std_lexem) {
+            Some(existing) if existing.type_info.is_some() || row.type_info.is_none() => {},
+            _ => { spellings.insert(row.std_lexem, row); }
+        }
+    }
+    spellings.into_iter().map(|(id, row)| (id, row.lexem.clone())).collect()
+}
@@ -55,10 +55,14 @@ impl std::fmt::Display for Logging {
     }
 }
 
+/// `~/.local/share/usqlrepl`, home of the logs and the program cache.
+pub fn data_dir() -> std::path::PathBuf {
+    my_home().unwrap().unwrap().join(".local/share/usqlrepl")
+}
+
 impl Default for Logging {
     fn default() -> Self {
-        let home = my_home().unwrap().unwrap();
-        let folder = format!("{}/.local/share/usqlrepl/logs", home.to_str().unwrap());
+        let folder = format!("{}/logs", data_dir().to_str().unwrap());
         Self {
             level: LoggingLevel::Info,
             folder,
@@ -0,0 +1,52 @@
+//! Tree-walking `Interpreter` vs bytecode `Vm` on the same parsed programs.
+//!
+//! cargo bench -p tests --bench engines
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use interpreter::interpreter::Interpreter;
+use interpreter::vm::Vm;
+use lexer::ast::{AstParser, Stmt};
+use std::hint::black_box;
+use tests::test_helper::lex;
+
+const FACTORIAL: &str = "
+fn Factorial(int n) -> int {
+    if (n < 2) { return 1 }
+    return n * Factorial(n - 1)
+}
+let mut i = 0
+while (i < 200) {
+    let f = Factorial(20)
+    i = i + 1
+}
+";
+
+const LOOP: &str = "
+let mut sum = 0
+let mut i = 0
+while (i < 20000) {
+    let step = i * 2
+    sum = sum + step / 2
+    i = i + 1
+}
+";
+
+fn bench_program(c: &mut Criterion, name: &str, source: &str) {
+    let statements: Vec<Stmt> = AstParser::new(lex(source)).parse().unwrap();
+    let mut group = c.benchmark_group(name);
+    group.bench_function("interpreter", |b| {
+        b.iter(|| Interpreter::new().interpret(black_box(statements.clone())).unwrap())
+    });
+    group.bench_function("vm", |b| {
+        b.iter(|| Vm::new().interpret(black_box(statements.clone())).unwrap())
+    });
+    group.finish();
+}
+
+fn engines(c: &mut Criterion) {
+    bench_program(c, "factorial", FACTORIAL);
+    bench_program(c, "loop", LOOP);
+}
+
+criterion_group!(benches, engines);
+criterion_main!(benches);
@@ -0,0 +1,69 @@
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use db::model::{GlossaryEntry, StdLexeme};
+    use lexer::ast::AstParser;
+    use lexer::cache::{ProgramCache, lexicon_fingerprint};
+    use lexer::serialize::ParsedProgram;
+    use lexer::tokenizer::std_ids;
+    use tempfile::TempDir;
+    use crate::test_helper::*;
+
+    #[test]
+    fn test_cache_hits_only_for_same_source_language_and_lexicon() {
+        let dir = TempDir::with_prefix("usqlrepl-").unwrap();
+        let cache = ProgramCache::new(dir.path().join("cache"));
+        let path = Path::new("program.v");
+        let source = "друк(1)";
+        let statements = AstParser::new(vec![
+            t("друк", std_ids::PRINT), t("(", ID_L_PAREN), t("1", ID_NUM), t(")", ID_R_PAREN),
+        ]).parse().unwrap();
+        let program = ParsedProgram {
+            statements,
+            glossary: vec![GlossaryEntry {
+                id: 1,
+                lang_name: "UA".to_string(),
+                identifier: "Тварина".to_string(),
+                canonical: "animal".to_string(),
+            }],
+            lexems: vec![lexem_row(228, "UA", "ціле", Some("Int"), std_ids::INT_TYPE)],
+        };
+
+        assert_eq!(cache.load(path, source, "UA", 7), None);
+        cache.store(path, source, "UA", 7, &program);
+
+        assert_eq!(cache.load(path, source, "UA", 7), Some(program));
+        assert_eq!(cache.load(path, "друк(2)", "UA", 7), None);
+        assert_eq!(cache.load(path, source, "EN", 7), None);
+        assert_eq!(cache.load(path, source, "UA", 8), None);
+        assert_eq!(cache.load(Path::new("other.v"), source, "UA", 7), None);
+    }
+
+    #[test]
+    fn test_lexicon_fingerprint_follows_row_changes() {
+        let std_lexem = StdLexeme { id: 46, name: "function".to_string(), lexem_type: "Keyword".to_string() };
+        let rows = vec![(lexem_row(197, "UA", "Функція", None, std_ids::FUNCTION), std_lexem.clone())];
+        let renamed = vec![(lexem_row(197, "UA", "Функц", None, std_ids::FUNCTION), std_lexem.clone())];
+        let retyped = vec![(
+            lexem_row(197, "UA", "Функція", None, std_ids::FUNCTION),
+            StdLexeme { lexem_type: "Operator".to_string(), ..std_lexem },
+        )];
+
+        let glossary = vec![GlossaryEntry {
+            id: 1,
+            lang_name: "UA".to_string(),
+            identifier: "Тварина".to_string(),
+            canonical: "animal".to_string(),
+        }];
+        let realiased = vec![GlossaryEntry { identifier: "Звір".to_string(), ..glossary[0].clone() }];
+
+        let fingerprint = lexicon_fingerprint(&rows, &glossary);
+        assert_eq!(fingerprint, lexicon_fingerprint(&rows.clone(), &glossary.clone()));
+        assert_ne!(fingerprint, lexicon_fingerprint(&renamed, &glossary));
+        assert_ne!(fingerprint, lexicon_fingerprint(&retyped, &glossary));
+        assert_ne!(fingerprint, lexicon_fingerprint(&rows, &realiased));
+        assert_ne!(fingerprint, lexicon_fingerprint(&rows, &[]));
+        // Відбиток не залежить від збірки: FNV-1a порожнього лексикону
+        assert_eq!(lexicon_fingerprint(&[], &[]), 0x4d25_767f_9dce_13f5);
+    }
+}
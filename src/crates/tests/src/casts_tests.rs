@@ -0,0 +1,46 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use lexer::ast::{AstParser, DataType};
+    use interpreter::checker::TypeChecker;
+    use interpreter::interpreter::RuntimeValue;
+    use crate::test_helper::*;
+
+    #[test]
+    fn test_input_converts_to_numbers() {
+        let source = "let x = to_int(input()) + to_float(input())";
+        assert_eq!(eval_both(source, &EvalConfig { input: &[" 40 ", "2.5"], ..EvalConfig::default() }), Ok(RuntimeValue::Number(42.5)));
+        assert_eq!(
+            eval_both(source, &EvalConfig { input: &["сорок"], ..EvalConfig::default() }).unwrap_err().message,
+            "Cannot convert 'сорок' to Int"
+        );
+        assert_eq!(eval("let x = to_int(\"4.2\")").unwrap_err().message, "Cannot convert '4.2' to Int");
+        assert_eq!(eval("let x = to_float(\"inf\")").unwrap_err().message, "Cannot convert 'inf' to Float");
+        assert_eq!(eval("let x = to_int(0 - 3.7)"), Ok(RuntimeValue::Number(-3.0)));
+    }
+
+    #[test]
+    fn test_to_string_and_to_bool() {
+        assert_eq!(eval("let x = to_string(2.5) + to_string([1, 2])"), Ok(RuntimeValue::String("2.5[1, 2]".to_string())));
+        assert_eq!(eval("let x = to_bool(\"true\")"), Ok(RuntimeValue::Bool(true)));
+        assert_eq!(eval("let x = to_bool(0)"), Ok(RuntimeValue::Bool(false)));
+        assert_eq!(eval("let x = to_bool(\"так\")").unwrap_err().message, "Cannot convert 'так' to Bool");
+        assert_eq!(eval("let x = to_int([1])").unwrap_err().message, "Cannot convert List to Int");
+    }
+
+    #[test]
+    fn test_type_of_uses_script_spellings() {
+        let type_names = HashMap::from([(DataType::Int, "ціле".to_string()), (DataType::Float, "дійсне".to_string())]);
+        assert_eq!(
+            eval_both("let x = type_of(2) + \" \" + type_of(2.5) + \" \" + type_of(\"a\")", &EvalConfig { type_names, ..EvalConfig::default() }),
+            Ok(RuntimeValue::String("ціле дійсне String".to_string()))
+        );
+        assert_eq!(
+            eval("struct Point { x: int }\nlet x = type_of(Point(1))"),
+            Ok(RuntimeValue::String("Point".to_string()))
+        );
+
+        let statements = AstParser::new(lex("let n: int = to_int(input())\nlet s: string = type_of(n)")).parse().unwrap();
+        assert!(TypeChecker::new().check(&statements).is_ok());
+    }
+}
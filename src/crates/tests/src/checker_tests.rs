@@ -0,0 +1,178 @@
+#[cfg(test)]
+mod tests {
+    use lexer::ast::AstParser;
+    use lexer::tokenizer::{Span, std_ids};
+    use interpreter::checker::TypeChecker;
+    use crate::test_helper::*;
+
+    fn animal_struct() -> Vec<lexer::tokenizer::Token> {
+        vec![
+            t("struct", ID_STRUCT),
+            t("Animal", ID_NAME),
+            t("{", ID_L_BRACE),
+            t("id", ID_NAME),
+            t(":", ID_COLON),
+            t("int", ID_INT),
+            t(",", ID_COMMA),
+            t("name", ID_NAME),
+            t(":", ID_COLON),
+            t("string", std_ids::STRING_TYPE),
+            t("}", ID_R_BRACE),
+        ]
+    }
+
+    #[test]
+    fn test_well_typed_program_passes() {
+        let mut tokens = animal_struct();
+        tokens.extend(vec![
+            t("let", ID_VAR),
+            t("a", ID_NAME),
+            t("=", ID_EQ),
+            t("Animal", ID_NAME),
+            t("(", ID_L_PAREN),
+            t("1", ID_NUM),
+            t(",", ID_COMMA),
+            t("Їжачок", std_ids::STRING_LITERAL),
+            t(")", ID_R_PAREN),
+            t("print", std_ids::PRINT),
+            t("(", ID_L_PAREN),
+            t("a", ID_NAME),
+            t(".", std_ids::DOT),
+            t("name", ID_NAME),
+            t("+", std_ids::PLUS),
+            t("!", std_ids::STRING_LITERAL),
+            t(")", ID_R_PAREN),
+        ]);
+
+        let statements = AstParser::new(tokens).parse().unwrap();
+        assert_eq!(TypeChecker::new().check(&statements), Ok(()));
+    }
+
+    #[test]
+    fn test_reports_all_mismatches() {
+        let mut tokens = animal_struct();
+        tokens.extend(vec![
+            // let a = Animal("x", 5)
+            t("let", ID_VAR),
+            t("a", ID_NAME),
+            t("=", ID_EQ),
+            t("Animal", ID_NAME),
+            t("(", ID_L_PAREN),
+            t("x", std_ids::STRING_LITERAL),
+            t(",", ID_COMMA),
+            t("5", ID_NUM),
+            t(")", ID_R_PAREN),
+            // a.age
+            t("a", ID_NAME),
+            t(".", std_ids::DOT),
+            t("age", ID_NAME),
+            // a.id - "b"
+            t("a", ID_NAME),
+            t(".", std_ids::DOT),
+            t("id", ID_NAME),
+            t("-", std_ids::MINUS),
+            t("b", std_ids::STRING_LITERAL),
+        ]);
+
+        let statements = AstParser::new(tokens).parse().unwrap();
+        let errors = TypeChecker::new().check(&statements).unwrap_err();
+
+        assert_eq!(errors.len(), 4, "{:?}", errors);
+        assert!(errors[0].message.contains("'id'"));
+        assert!(errors[1].message.contains("'name'"));
+        assert!(errors[2].message.contains("no field 'age'"));
+        assert!(errors[3].message.contains("'-'"));
+    }
+
+    #[test]
+    fn test_reports_immutable_and_annotated_assignments() {
+        let tokens = vec![
+            t("let", ID_VAR),
+            t("x", ID_NAME),
+            t(":", ID_COLON),
+            t("string", std_ids::STRING_TYPE),
+            t("=", ID_EQ),
+            t("1", ID_NUM),
+            t("x", ID_NAME),
+            t("=", ID_EQ),
+            t("a", std_ids::STRING_LITERAL),
+        ];
+
+        let statements = AstParser::new(tokens).parse().unwrap();
+        let errors = TypeChecker::new().check(&statements).unwrap_err();
+
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert_eq!(errors[0].message, "Variable 'x' is declared as String, got Int");
+        assert_eq!(errors[1].message, "Cannot assign twice to immutable variable 'x'");
+    }
+
+    #[test]
+    fn test_field_assignment_is_checked() {
+        let statements = AstParser::new(lex("
+struct Point { x: int }
+let p = Point(1)
+p.x = 2
+p.x = \"two\"
+p.z = 3
+")).parse().unwrap();
+        let errors = TypeChecker::new().check(&statements).unwrap_err();
+
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert_eq!(errors[0].message, "Field 'x' of 'Point' expects Int, got String");
+        assert_eq!(errors[1].message, "Struct 'Point' has no field 'z'");
+    }
+
+    #[test]
+    fn test_integer_division_has_unknown_type() {
+        let statements = AstParser::new(lex("
+let half: int = 4 / 2
+let third: int = 5 / 2.5
+")).parse().unwrap();
+        let errors = TypeChecker::new().check(&statements).unwrap_err();
+
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert_eq!(errors[0].message, "Variable 'third' is declared as Int, got Float");
+    }
+
+    #[test]
+    fn test_functions_know_nothing_of_names_declared_outside() {
+        // Як і в рушіях: такі імена шукають серед змінних тих, хто викликав
+        let statements = AstParser::new(lex("
+let s = \"a\"
+let fixed = 1
+fn Outer(int n) {
+    let mut local: int = 1
+    fn Inner() -> int {
+        fixed = 2
+        local = \"x\"
+        return s + n
+    }
+    local = \"y\"
+}
+")).parse().unwrap();
+        let errors = TypeChecker::new().check(&statements).unwrap_err();
+
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert_eq!(errors[0].message, "Variable 'local' is declared as Int, got String");
+        assert_eq!(errors[0].span, Some(Span { line: 11, column: 5 }));
+    }
+
+    #[test]
+    fn test_type_errors_point_at_their_node() {
+        let statements = AstParser::new(lex("
+let s: string = 1
+fn F() -> int {
+    return \"x\"
+}
+struct P { q: Missing }
+")).parse().unwrap();
+        let errors = TypeChecker::new().check(&statements).unwrap_err();
+
+        let spans: Vec<_> = errors.iter().map(|e| e.span).collect();
+        assert_eq!(spans, [
+            Some(Span { line: 2, column: 5 }),
+            Some(Span { line: 4, column: 5 }),
+            Some(Span { line: 6, column: 8 }),
+        ], "{:?}", errors);
+    }
+}
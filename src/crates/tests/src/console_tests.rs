@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod tests {
+    use lexer::ast::AstParser;
+    use std::cell::RefCell;
+    use std::io;
+    use std::rc::Rc;
+    use interpreter::console::{OutputSink, ScriptedInput};
+    use interpreter::interpreter::Interpreter;
+    use interpreter::vm::Vm;
+    use crate::test_helper::*;
+
+    #[test]
+    fn test_print_and_input_use_the_given_streams() {
+        let source = "
+let name = input(\"Name? \")
+let age = input()
+print(\"Hi\", name, len(age))
+let rest = input(\"More? \")
+print(len(rest))";
+        let config = EvalConfig { input: &["  Olena ", "42"], ..EvalConfig::default() };
+        eval_both(source, &config).unwrap();
+        assert_eq!(config.output.contents(), "Name? Hi Olena 2\nMore? 0\n");
+    }
+
+    /// Records writes, and flushes as `<flush>`.
+    #[derive(Clone, Default)]
+    struct Recording(Rc<RefCell<Vec<String>>>);
+
+    impl OutputSink for Recording {
+        fn write(&mut self, text: &str) -> io::Result<()> {
+            self.0.borrow_mut().push(text.to_string());
+            Ok(())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.0.borrow_mut().push("<flush>".to_string());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_output_is_flushed_only_before_input() {
+        let statements = AstParser::new(lex("print(1)\nlet x = input(\"? \")\nprint(x)")).parse().unwrap();
+        let expected = ["1\n", "? ", "<flush>", "a\n"];
+
+        let interp_output = Recording::default();
+        let mut interp = Interpreter::new();
+        interp.output = Box::new(interp_output.clone());
+        interp.input = Box::new(ScriptedInput::new(["a"]));
+        interp.interpret(statements.clone()).unwrap();
+        assert_eq!(*interp_output.0.borrow(), expected);
+
+        let vm_output = Recording::default();
+        let mut vm = Vm::new();
+        vm.output = Box::new(vm_output.clone());
+        vm.input = Box::new(ScriptedInput::new(["a"]));
+        vm.interpret(statements).unwrap();
+        assert_eq!(*vm_output.0.borrow(), expected);
+    }
+}
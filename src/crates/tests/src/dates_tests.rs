@@ -0,0 +1,79 @@
+#[cfg(test)]
+mod tests {
+    use lexer::lexicon::MemoryLexicon;
+    use lexer::tokenizer::{self, std_ids};
+    use interpreter::dates::DateNames;
+    use interpreter::error::RuntimeError;
+    use interpreter::interpreter::RuntimeValue;
+    use crate::test_helper::*;
+
+    /// The `UA` date names of the migration.
+    fn ukrainian() -> DateNames {
+        let names = [
+            "січень", "лютий", "березень", "квітень", "травень", "червень",
+            "липень", "серпень", "вересень", "жовтень", "листопад", "грудень",
+            "понеділок", "вівторок", "середа", "четвер", "п'ятниця", "субота", "неділя",
+        ];
+        DateNames::localized(|id| names.get(id.checked_sub(std_ids::JANUARY)? as usize).map(|name| name.to_string()))
+    }
+
+    fn string(value: &str) -> Result<RuntimeValue, RuntimeError> {
+        Ok(RuntimeValue::String(value.to_string()))
+    }
+
+    #[test]
+    fn test_dates_are_timestamps() {
+        assert_eq!(eval("let x = date(2024, 1, 31)"), Ok(RuntimeValue::Number(1_706_659_200.0)));
+        assert_eq!(
+            eval("let x = format_date(date(2024, 1, 1) + days(1) + hours(12.5), \"%Y-%m-%d %H:%M\")"),
+            string("2024-01-02 12:30")
+        );
+        assert_eq!(eval("let t = add_months(date(2024, 1, 31), 1)\nlet x = month(t) * 100 + day(t)"), Ok(RuntimeValue::Number(229.0)));
+        assert_eq!(eval("let x = year(add_months(date(2024, 3, 1), 0 - 3))"), Ok(RuntimeValue::Number(2023.0)));
+        assert_eq!(eval("let x = now() > date(2024, 1, 1)"), Ok(RuntimeValue::Bool(true)));
+        assert_eq!(eval("let x = date(2023, 2, 29)").unwrap_err().message, "Invalid date 2023-02-29");
+    }
+
+    #[test]
+    fn test_names_follow_the_script_language() {
+        let source = "let x = format_date(date(2024, 3, 8), \"%d %B (%b), %A\")";
+        assert_eq!(eval(source), string("08 March (Mar), Friday"));
+        assert_eq!(eval_both(source, &EvalConfig { date_names: ukrainian(), ..EvalConfig::default() }), string("08 березень (бер), п'ятниця"));
+
+        let parse = "let x = parse_date(\"8 березень 2024 14:30\", \"%d %B %Y %H:%M\") - date(2024, 3, 8)";
+        assert_eq!(eval_both(parse, &EvalConfig { date_names: ukrainian(), ..EvalConfig::default() }), Ok(RuntimeValue::Number(52_200.0)));
+        assert_eq!(
+            eval("let x = parse_date(\"вчора\", \"%Y-%m-%d\")").unwrap_err().message,
+            "Cannot parse date 'вчора' with format '%Y-%m-%d'"
+        );
+    }
+
+    #[test]
+    fn test_formatted_dates_parse_back() {
+        // Дні 11..22 різних місяців дають усі дні тижня, зокрема середу («сер», як серпень)
+        let source = "
+let long = \"%A, %d %B %Y %H:%M\"
+let short = \"%a %d %b %Y\"
+let mut m = 1
+let mut x = 0
+while (m < 13) {
+    let d = date(2024, m, m + 10)
+    x = x + abs(parse_date(format_date(d + hours(m), long), long) - d - hours(m))
+    x = x + abs(parse_date(format_date(d, short), short) - d)
+    m = m + 1
+}";
+        assert_eq!(eval(source), Ok(RuntimeValue::Number(0.0)));
+        assert_eq!(eval_both(source, &EvalConfig { date_names: ukrainian(), ..EvalConfig::default() }), Ok(RuntimeValue::Number(0.0)));
+
+        let parse = "let x = parse_date(\"сер 14 сер 2024\", \"%a %d %b %Y\") - date(2024, 8, 14)";
+        assert_eq!(eval_both(parse, &EvalConfig { date_names: ukrainian(), ..EvalConfig::default() }), Ok(RuntimeValue::Number(0.0)));
+    }
+
+    #[test]
+    fn test_date_names_stay_identifiers_in_source() {
+        let mut lexicon = MemoryLexicon::default();
+        lexicon.insert("UA", "понеділок", std_ids::MONDAY, std_ids::DATE_NAME);
+        let tokens = tokenizer::Parser::new(&lexicon).parse("понеділок");
+        assert_eq!(tokens[0].std_token_id, std_ids::IDENTIFIER);
+    }
+}
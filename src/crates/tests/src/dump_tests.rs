@@ -0,0 +1,89 @@
+#[cfg(test)]
+mod tests {
+    use lexer::ast::AstParser;
+    use db::model::GlossaryEntry;
+    use lexer::serialize::{self, ParsedProgram, SerializeError};
+    use lexer::tokenizer::{Token, std_ids};
+    use serde_json::json;
+    use crate::test_helper::*;
+
+    #[test]
+    fn test_token_json_has_position_and_language() {
+        let token = Token { lang: Some("UA".to_string()), ..t_at("функція", ID_FUNC, 3, 7) };
+
+        assert_eq!(serde_json::to_value(&token).unwrap(), json!({
+            "value": "функція",
+            "token_type": "TestType",
+            "std_token_id": std_ids::FUNCTION,
+            "lexem": "функція",
+            "span": { "line": 3, "column": 7 },
+            "lang": "UA",
+        }));
+    }
+
+    #[test]
+    fn test_ast_json_shape() {
+        // змінна x: ціле = 1
+        let tokens = vec![
+            t("let", ID_VAR),
+            t("x", ID_NAME),
+            t(":", ID_COLON),
+            t("ціле", ID_INT),
+            t("=", ID_EQ),
+            t("1", ID_NUM),
+        ];
+        let statements = AstParser::new(tokens).parse().unwrap();
+
+        assert_eq!(serde_json::to_value(&statements).unwrap(), json!([
+            { "VariableDeclaration": {
+                "name": "x",
+                "mutable": false,
+                "data_type": "Int",
+                "value": { "Number": 1.0 },
+                "span": { "line": 0, "column": 0 },
+            } }
+        ]));
+    }
+
+    #[test]
+    fn test_ast_round_trips_through_json_and_binary() {
+        // функція F(ціле n) -> ціле { якщо (n < 2) { повернути n } інакше { повернути F(n - 1) } }
+        let tokens = vec![
+            t("fn", ID_FUNC), t("F", ID_NAME), t("(", ID_L_PAREN), t("ціле", ID_INT), t("n", ID_NAME),
+            t(")", ID_R_PAREN), t("->", std_ids::ARROW), t("ціле", ID_INT), t("{", ID_L_BRACE),
+            t("if", std_ids::IF), t("(", ID_L_PAREN), t("n", ID_NAME), t("<", std_ids::LT), t("2", ID_NUM),
+            t(")", ID_R_PAREN), t("{", ID_L_BRACE), t("return", std_ids::RETURN), t("n", ID_NAME), t("}", ID_R_BRACE),
+            t("else", std_ids::ELSE), t("{", ID_L_BRACE), t("return", std_ids::RETURN), t("F", ID_NAME),
+            t("(", ID_L_PAREN), t("n", ID_NAME), t("-", std_ids::MINUS), t("1", ID_NUM), t(")", ID_R_PAREN),
+            t("}", ID_R_BRACE), t("}", ID_R_BRACE),
+            t_at("print", std_ids::PRINT, 2, 1), t("(", ID_L_PAREN), t("текст", std_ids::STRING_LITERAL), t(")", ID_R_PAREN),
+        ];
+        let program = ParsedProgram {
+            statements: AstParser::new(tokens).parse().unwrap(),
+            glossary: vec![GlossaryEntry {
+                id: 1,
+                lang_name: "UA".to_string(),
+                identifier: "Ф".to_string(),
+                canonical: "F".to_string(),
+            }],
+            lexems: vec![lexem_row(228, "UA", "ціле", Some("Int"), std_ids::INT_TYPE)],
+        };
+
+        let json = serialize::to_json(&program);
+        assert_eq!(serialize::from_json(&json).unwrap(), program);
+        assert_eq!(serialize::from_bytes(json.as_bytes()).unwrap(), program);
+
+        let binary = serialize::to_binary(&program);
+        assert!(binary.len() < json.len());
+        assert_eq!(serialize::from_bytes(&binary).unwrap(), program);
+    }
+
+    #[test]
+    fn test_binary_ast_rejects_other_versions() {
+        let mut binary = serialize::to_binary(&ParsedProgram::default());
+        binary[serialize::MAGIC.len()] = serialize::FORMAT_VERSION + 1;
+
+        assert!(matches!(serialize::from_binary(&binary), Err(SerializeError::UnsupportedVersion(_))));
+        assert!(matches!(serialize::from_binary(b"{}"), Err(SerializeError::NotAnAst)));
+    }
+}
@@ -0,0 +1,106 @@
+#[cfg(test)]
+mod tests {
+    use embed::engine::{Engine, EngineError};
+    use interpreter::interpreter::RuntimeValue;
+    use interpreter::natives::{Arity, Native};
+    use lexer::ast::DataType;
+    use lexer::lexicon::MemoryLexicon;
+    use lexer::tokenizer::std_ids;
+
+    fn lexicon() -> MemoryLexicon {
+        let mut lexicon = MemoryLexicon::default();
+        let words = [
+            ("let", std_ids::LET, "Keyword"),
+            ("mut", std_ids::MUT, "Keyword"),
+            ("fn", std_ids::FUNCTION, "Keyword"),
+            ("return", std_ids::RETURN, "Keyword"),
+            ("int", std_ids::INT_TYPE, "Keyword"),
+            ("len", std_ids::LEN, "NativeFunc"),
+            ("=", std_ids::ASSIGN, "Operator"),
+            ("+", std_ids::PLUS, "Operator"),
+            ("*", std_ids::MULTIPLY, "Operator"),
+            ("(", std_ids::L_PAREN, "Delimiter"),
+            (")", std_ids::R_PAREN, "Delimiter"),
+            ("{", std_ids::L_BRACE, "Delimiter"),
+            ("}", std_ids::R_BRACE, "Delimiter"),
+            (",", std_ids::COMMA, "Delimiter"),
+            (":", std_ids::COLON, "Delimiter"),
+            ("->", std_ids::ARROW, "Delimiter"),
+        ];
+        for (word, id, lexem_type) in words {
+            lexicon.insert("EN", word, id, lexem_type);
+        }
+        lexicon
+    }
+
+    fn greet() -> Native {
+        Native::new("greet", Arity::Exact(1), |_, args| {
+            let name = String::try_from(args[0].clone())?;
+            Ok(format!("Hello, {}!", name).into())
+        })
+        .param("name", Some(DataType::String))
+        .returns(DataType::String)
+    }
+
+    #[test]
+    fn test_engine_runs_scripts_with_host_values() {
+        let mut engine = Engine::new(lexicon());
+        engine.register("greet", greet());
+        engine.set_global("user", "Olena");
+        engine.set_global("base", 40i64);
+
+        engine.eval("fn Add(int a, int b) -> int { return a + b }").unwrap();
+        engine.eval("let message = greet(user)\nlet total = Add(base, len(\"ab\"))").unwrap();
+
+        let message = String::try_from(engine.global("message").unwrap()).unwrap();
+        assert_eq!(message, "Hello, Olena!");
+        assert_eq!(i64::try_from(engine.global("total").unwrap()), Ok(42));
+        assert_eq!(engine.global("missing"), None);
+    }
+
+    #[test]
+    fn test_engine_reports_errors() {
+        let mut engine = Engine::new(lexicon());
+        engine.register("greet", greet());
+
+        let Err(EngineError::Type(errors)) = engine.eval("let x: int = greet(\"a\")") else {
+            panic!("expected a type error");
+        };
+        assert_eq!(errors[0].message, "Variable 'x' is declared as Int, got String");
+
+        // Тип глобальної змінної хоста відомий лише під час виконання
+        engine.set_global("user", 7i64);
+        let Err(EngineError::Runtime(error)) = engine.eval("let y = greet(user)") else {
+            panic!("expected a runtime error");
+        };
+        assert_eq!(error.message, "Argument 'name' of 'greet' expects String, got Number");
+
+        assert!(matches!(engine.eval("let = 1"), Err(EngineError::Syntax(_))));
+    }
+
+    #[test]
+    fn test_failed_eval_leaves_no_declarations_behind() {
+        let mut engine = Engine::new(lexicon());
+        engine.register("greet", greet());
+
+        assert!(matches!(engine.eval("fn f() -> int { return 1 }\nlet x: int = greet(\"a\")"), Err(EngineError::Type(_))));
+        let Err(EngineError::Type(errors)) = engine.eval("let y = f()") else {
+            panic!("'f' must not be known after the failed eval");
+        };
+        assert_eq!(errors[0].message, "Undefined function 'f'");
+
+        engine.eval("fn f() -> int { return 1 }").unwrap();
+        engine.eval("let y = f()").unwrap();
+        assert_eq!(engine.global("y"), Some(RuntimeValue::Number(1.0)));
+    }
+
+    #[test]
+    fn test_value_conversions() {
+        assert_eq!(RuntimeValue::from(2i64), RuntimeValue::Number(2.0));
+        assert_eq!(RuntimeValue::from(None::<bool>), RuntimeValue::Null);
+        assert_eq!(bool::try_from(RuntimeValue::Bool(true)), Ok(true));
+        assert_eq!(f64::try_from(RuntimeValue::Number(2.5)), Ok(2.5));
+        assert_eq!(i64::try_from(RuntimeValue::Number(2.5)).unwrap_err().message, "Expected Int, got 2.5");
+        assert_eq!(String::try_from(RuntimeValue::Bool(false)).unwrap_err().message, "Expected String, got Bool");
+    }
+}
@@ -0,0 +1,62 @@
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+    use interpreter::interpreter::RuntimeValue;
+    use interpreter::permissions::{Permission, Permissions};
+    use tempfile::TempDir;
+    use crate::test_helper::*;
+
+    /// `source` with `{dir}` replaced by `dir`.
+    fn in_dir(source: &str, dir: &Path) -> String {
+        source.replace("{dir}", &dir.to_string_lossy())
+    }
+
+    /// Empties `dir`, so each engine starts without the other's files.
+    fn empty(dir: &Path) {
+        fs::remove_dir_all(dir).unwrap();
+        fs::create_dir(dir).unwrap();
+    }
+
+    fn read_write() -> Permissions {
+        Permissions::default().allow(Permission::Read).allow(Permission::Write)
+    }
+
+    #[test]
+    fn test_write_append_and_read_back() {
+        let dir = TempDir::with_prefix("usqlrepl-").unwrap();
+        let reset = || empty(dir.path());
+        let config = EvalConfig { permissions: read_write(), reset: Some(&reset), ..EvalConfig::default() };
+        let source = "
+write_file(\"{dir}/вірш.txt\", \"Реве та стогне\")
+append_file(\"{dir}/вірш.txt\", \" Дніпр широкий\")
+write_file(\"{dir}/b.txt\", \"\")
+let x = read_file(\"{dir}/вірш.txt\") + \" \" + to_string(list_dir(\"{dir}\")) + \" \" + to_string(exists(\"{dir}/c.txt\"))";
+        assert_eq!(
+            eval_both(&in_dir(source, dir.path()), &config),
+            Ok(RuntimeValue::String("Реве та стогне Дніпр широкий [b.txt, вірш.txt] false".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_file_errors_are_runtime_errors() {
+        let dir = TempDir::with_prefix("usqlrepl-").unwrap();
+        let with = |permissions| EvalConfig { permissions, ..EvalConfig::default() };
+
+        let message = eval_both(&in_dir("let x = read_file(\"{dir}/missing.txt\")", dir.path()), &with(read_write()))
+            .unwrap_err()
+            .message;
+        assert!(message.starts_with("Failed to read file '"), "{}", message);
+        assert!(message.contains("missing.txt"), "{}", message);
+
+        assert_eq!(
+            eval_both(&in_dir("let x = exists(\"{dir}\")", dir.path()), &with(Permissions::default())).unwrap_err().message,
+            "Permission denied: 'exists' needs read access, run with --allow-read"
+        );
+        let readonly = Permissions::default().allow(Permission::Read);
+        assert_eq!(
+            eval_both(&in_dir("write_file(\"{dir}/a.txt\", \"x\")\nlet x = 1", dir.path()), &with(readonly)).unwrap_err().message,
+            "Permission denied: 'write_file' needs write access, run with --allow-write"
+        );
+    }
+}
@@ -0,0 +1,114 @@
+#[cfg(test)]
+mod tests {
+    use lexer::format::format;
+    use lexer::glossary::Glossary;
+    use lexer::tokenizer::std_ids;
+    use lexer::translate::Translator;
+    use crate::test_helper::*;
+
+    fn ua(value: &str, id: u32) -> lexer::tokenizer::Token {
+        t_lang(value, id, "UA")
+    }
+
+    #[test]
+    fn test_format_normalizes_indentation_and_keeps_comments() {
+        let ukrainian = vec![lexem_row(197, "UA", "Функція", None, std_ids::FUNCTION)];
+
+        // функція F(ціле n)->ціле{   // факторіал
+        //         якщо (n<2) { повернути 1 }
+        //
+        //
+        //   // рекурсія
+        //   повернути n*F(n-1)
+        // }
+        let tokens = vec![
+            ua("функція", ID_FUNC), ws(" "), t("F", ID_NAME), ua("(", ID_L_PAREN), ua("ціле", ID_INT), ws(" "),
+            t("n", ID_NAME), ua(")", ID_R_PAREN), ua("->", std_ids::ARROW), ua("ціле", ID_INT), ua("{", ID_L_BRACE),
+            ws("   "), comment("// факторіал"), ws("\n        "),
+            ua("якщо", std_ids::IF), ws(" "), ua("(", ID_L_PAREN), t("n", ID_NAME), ua("<", std_ids::LT), t("2", ID_NUM),
+            ua(")", ID_R_PAREN), ws(" "), ua("{", ID_L_BRACE), ws(" "), ua("повернути", std_ids::RETURN), ws(" "),
+            t("1", ID_NUM), ws(" "), ua("}", ID_R_BRACE), ws("\n\n\n  "),
+            comment("// рекурсія"), ws("\n  "),
+            ua("повернути", std_ids::RETURN), ws(" "), t("n", ID_NAME), ua("*", std_ids::MULTIPLY), t("F", ID_NAME),
+            ua("(", ID_L_PAREN), t("n", ID_NAME), ua("-", std_ids::MINUS), t("1", ID_NUM), ua(")", ID_R_PAREN),
+            ws("\n"), ua("}", ID_R_BRACE),
+        ];
+
+        let output = format(&tokens, &Translator::new("UA", &ukrainian, Glossary::default())).unwrap();
+
+        assert_eq!(output, "\
+функція F(ціле n) -> ціле { // факторіал
+    якщо (n < 2) {
+        повернути 1
+    }
+
+    // рекурсія
+    повернути n * F(n - 1)
+}
+");
+    }
+
+    #[test]
+    fn test_format_translates_and_keeps_needed_parentheses() {
+        let english = vec![
+            lexem_row(124, "EN", "let", None, std_ids::LET),
+            lexem_row(126, "EN", "mut", None, std_ids::MUT),
+            lexem_row(228, "EN", "int", Some("Int"), std_ids::INT_TYPE),
+            lexem_row(241, "EN", "print", None, std_ids::PRINT),
+        ];
+
+        // змінна змінний x: ціле = (1 + 2) * (3 - (4 - 5))   друк(x)
+        let tokens = vec![
+            ua("змінна", ID_VAR), ws(" "), ua("змінний", std_ids::MUT), ws(" "), t("x", ID_NAME), ua(":", ID_COLON),
+            ws(" "), ua("ціле", ID_INT), ws(" "), ua("=", ID_EQ), ws(" "),
+            ua("(", ID_L_PAREN), t("1", ID_NUM), ua("+", std_ids::PLUS), t("2", ID_NUM), ua(")", ID_R_PAREN),
+            ua("*", std_ids::MULTIPLY),
+            ua("(", ID_L_PAREN), t("3", ID_NUM), ua("-", std_ids::MINUS),
+            ua("(", ID_L_PAREN), t("4", ID_NUM), ua("-", std_ids::MINUS), t("5", ID_NUM), ua(")", ID_R_PAREN),
+            ua(")", ID_R_PAREN), ws("   "),
+            ua("друк", std_ids::PRINT), ua("(", ID_L_PAREN), t("x", ID_NAME), ua(")", ID_R_PAREN), ws("\n"),
+        ];
+
+        let output = format(&tokens, &Translator::new("EN", &english, Glossary::default())).unwrap();
+
+        assert_eq!(output, "let mut x: int = (1 + 2) * (3 - (4 - 5))\nprint(x)\n");
+    }
+
+    #[test]
+    fn test_format_keeps_declarations_without_initializer() {
+        let english = vec![
+            lexem_row(124, "EN", "let", None, std_ids::LET),
+            lexem_row(126, "EN", "mut", None, std_ids::MUT),
+            lexem_row(228, "EN", "int", Some("Int"), std_ids::INT_TYPE),
+        ];
+
+        // змінна змінний x   змінна y: ціле
+        let tokens = vec![
+            ua("змінна", ID_VAR), ws(" "), ua("змінний", std_ids::MUT), ws(" "), t("x", ID_NAME), ws("   "),
+            ua("змінна", ID_VAR), ws(" "), t("y", ID_NAME), ua(":", ID_COLON), ws(" "), ua("ціле", ID_INT), ws("\n"),
+        ];
+
+        let output = format(&tokens, &Translator::new("EN", &english, Glossary::default())).unwrap();
+
+        assert_eq!(output, "let mut x\nlet y: int\n");
+    }
+
+    #[test]
+    fn test_format_rejects_literal_with_both_quotes() {
+        let english = vec![lexem_row(124, "EN", "let", None, std_ids::LET)];
+        let translator = Translator::new("EN", &english, Glossary::default());
+
+        // Токени з `'say "hi"'` та з рядком, якого лексер не видасть
+        let with_literal = |lexem: &str| {
+            let mut tokens = lex("let x = \"a\"");
+            tokens[3].lexem = lexem.to_string();
+            tokens
+        };
+        assert_eq!(format(&with_literal("say \"hi\""), &translator).unwrap(), "let x = 'say \"hi\"'\n");
+
+        let tokens = with_literal("it's \"hi\"");
+        let err = format(&tokens, &translator).unwrap_err();
+        assert_eq!(err.message, "String literal contains both ' and \", which cannot be written back");
+        assert_eq!(err.span, tokens[3].span);
+    }
+}
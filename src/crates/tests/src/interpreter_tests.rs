@@ -0,0 +1,188 @@
+#[cfg(test)]
+mod tests {
+    use lexer::ast::AstParser;
+    use lexer::tokenizer::{Span, std_ids};
+    use interpreter::interpreter::{Interpreter, RuntimeValue};
+    use db::model::GlossaryEntry;
+    use lexer::glossary::Glossary;
+    use crate::test_helper::*;
+
+    #[test]
+    fn test_invalid_operands_is_error() {
+        let tokens = vec![
+            t("1", ID_NUM),
+            t_at("+", std_ids::PLUS, 1, 3),
+            t("a", std_ids::STRING_LITERAL),
+        ];
+
+        let statements = AstParser::new(tokens).parse().unwrap();
+        let err = Interpreter::new().interpret(statements).unwrap_err();
+
+        assert!(err.message.contains("'+'"), "unexpected message: {}", err.message);
+        assert_eq!(err.span, Some(Span { line: 1, column: 3 }));
+        assert!(err.stack.is_empty());
+    }
+
+    #[test]
+    fn test_error_records_call_stack() {
+        let tokens = vec![
+            t("fn", ID_FUNC),
+            t("Broken", ID_NAME),
+            t("(", ID_L_PAREN),
+            t(")", ID_R_PAREN),
+            t("{", ID_L_BRACE),
+            t("missing", ID_NAME),
+            t("}", ID_R_BRACE),
+
+            t_at("Broken", ID_NAME, 3, 1),
+            t("(", ID_L_PAREN),
+            t(")", ID_R_PAREN),
+        ];
+
+        let statements = AstParser::new(tokens).parse().unwrap();
+        let err = Interpreter::new().interpret(statements).unwrap_err();
+
+        assert!(err.message.contains("missing"));
+        assert_eq!(err.stack.len(), 1);
+        assert_eq!(err.stack[0].function, "Broken");
+        assert_eq!(err.stack[0].call_site, Span { line: 3, column: 1 });
+    }
+
+    #[test]
+    fn test_return_type_is_enforced() {
+        let tokens = vec![
+            t("fn", ID_FUNC),
+            t("Name", ID_NAME),
+            t("(", ID_L_PAREN),
+            t(")", ID_R_PAREN),
+            t("->", std_ids::ARROW),
+            t("ціле", ID_INT),
+            t("{", ID_L_BRACE),
+            t("return", std_ids::RETURN),
+            t("Їжачок", std_ids::STRING_LITERAL),
+            t("}", ID_R_BRACE),
+
+            t_at("Name", ID_NAME, 2, 1),
+            t("(", ID_L_PAREN),
+            t(")", ID_R_PAREN),
+        ];
+
+        let statements = AstParser::new(tokens).parse().unwrap();
+        let err = Interpreter::new().interpret(statements).unwrap_err();
+
+        assert_eq!(err.message, "Function 'Name' must return Int, got String");
+        assert_eq!(err.stack[0].function, "Name");
+    }
+
+    #[test]
+    fn test_mutable_and_immutable_assignment() {
+        let tokens = vec![
+            t("let", ID_VAR),
+            t("mut", std_ids::MUT),
+            t("counter", ID_NAME),
+            t("=", ID_EQ),
+            t("1", ID_NUM),
+            t("counter", ID_NAME),
+            t("=", ID_EQ),
+            t("counter", ID_NAME),
+            t("+", std_ids::PLUS),
+            t("1", ID_NUM),
+
+            t("let", ID_VAR),
+            t("fixed", ID_NAME),
+            t("=", ID_EQ),
+            t("1", ID_NUM),
+            t_at("fixed", ID_NAME, 4, 1),
+            t("=", ID_EQ),
+            t("2", ID_NUM),
+        ];
+
+        let statements = AstParser::new(tokens).parse().unwrap();
+        let mut interp = Interpreter::new();
+        let err = interp.interpret(statements).unwrap_err();
+
+        assert_eq!(err.message, "Cannot assign twice to immutable variable 'fixed'");
+        assert_eq!(err.span, Some(Span { line: 4, column: 1 }));
+        assert_eq!(interp.global("counter"), Some(RuntimeValue::Number(2.0)));
+    }
+
+    #[test]
+    fn test_argument_and_field_types_are_enforced() {
+        let animal = vec![
+            t("struct", ID_STRUCT),
+            t("Animal", ID_NAME),
+            t("{", ID_L_BRACE),
+            t("id", ID_NAME),
+            t(":", ID_COLON),
+            t("ціле", ID_INT),
+            t(",", ID_COMMA),
+            t("name", ID_NAME),
+            t(":", ID_COLON),
+            t("рядок", std_ids::STRING_TYPE),
+            t("}", ID_R_BRACE),
+        ];
+
+        // Animal("x", 5)
+        let mut tokens = animal.clone();
+        tokens.extend(vec![
+            t("Animal", ID_NAME),
+            t("(", ID_L_PAREN),
+            t("x", std_ids::STRING_LITERAL),
+            t(",", ID_COMMA),
+            t("5", ID_NUM),
+            t(")", ID_R_PAREN),
+        ]);
+        let err = Interpreter::new().interpret(AstParser::new(tokens).parse().unwrap()).unwrap_err();
+        assert_eq!(err.message, "Field 'id' of 'Animal' expects Int, got String");
+
+        // fn CallMe(Animal Тварина) {}  CallMe(1)
+        let mut tokens = animal;
+        tokens.extend(vec![
+            t("fn", ID_FUNC),
+            t("CallMe", ID_NAME),
+            t("(", ID_L_PAREN),
+            t("Animal", ID_NAME),
+            t("Тварина", ID_NAME),
+            t(")", ID_R_PAREN),
+            t("{", ID_L_BRACE),
+            t("}", ID_R_BRACE),
+            t("CallMe", ID_NAME),
+            t("(", ID_L_PAREN),
+            t("1", ID_NUM),
+            t(")", ID_R_PAREN),
+        ]);
+        let err = Interpreter::new().interpret(AstParser::new(tokens).parse().unwrap()).unwrap_err();
+        assert_eq!(err.message, "Argument 'Тварина' of 'CallMe' expects Animal, got Number");
+    }
+
+    #[test]
+    fn test_glossary_aliases_share_binding() {
+        let entry = |id, lang: &str, identifier: &str| GlossaryEntry {
+            id,
+            lang_name: lang.to_string(),
+            identifier: identifier.to_string(),
+            canonical: "animal".to_string(),
+        };
+        let glossary = Glossary::new(&[entry(1, "EN", "animal"), entry(2, "UA", "Тварина")]);
+
+        // let mut Тварина = 1  animal = Тварина + 1
+        let tokens = vec![
+            t("let", ID_VAR),
+            t("mut", std_ids::MUT),
+            t("Тварина", ID_NAME),
+            t("=", ID_EQ),
+            t("1", ID_NUM),
+            t("animal", ID_NAME),
+            t("=", ID_EQ),
+            t("Тварина", ID_NAME),
+            t("+", std_ids::PLUS),
+            t("1", ID_NUM),
+        ];
+
+        let mut interp = Interpreter::with_glossary(glossary);
+        interp.interpret(AstParser::new(tokens).parse().unwrap()).unwrap();
+
+        assert_eq!(interp.global("animal"), Some(RuntimeValue::Number(2.0)));
+        assert_eq!(interp.global("Тварина"), Some(RuntimeValue::Number(2.0)));
+    }
+}
@@ -1,3 +1,40 @@
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 pub mod tests;
+#[cfg(test)]
+pub mod interpreter_tests;
+#[cfg(test)]
+pub mod checker_tests;
+#[cfg(test)]
+pub mod translate_tests;
+#[cfg(test)]
+pub mod format_tests;
+#[cfg(test)]
+pub mod dump_tests;
+#[cfg(test)]
+pub mod cache_tests;
+#[cfg(test)]
+pub mod vm_tests;
 pub mod test_helper;
+#[cfg(test)]
+pub mod resolver_tests;
+#[cfg(test)]
+pub mod limits_tests;
+#[cfg(test)]
+pub mod permissions_tests;
+#[cfg(test)]
+pub mod natives_tests;
+#[cfg(test)]
+pub mod embed_tests;
+#[cfg(test)]
+pub mod console_tests;
+#[cfg(test)]
+pub mod math_tests;
+#[cfg(test)]
+pub mod strings_tests;
+#[cfg(test)]
+pub mod casts_tests;
+#[cfg(test)]
+pub mod files_tests;
+#[cfg(test)]
+pub mod dates_tests;
\ No newline at end of file
@@ -0,0 +1,67 @@
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use interpreter::error::{Limit, RuntimeError};
+    use interpreter::interpreter::RuntimeValue;
+    use interpreter::limits::Limits;
+    use crate::test_helper::*;
+
+    fn under(limits: Limits) -> EvalConfig<'static> {
+        EvalConfig { limits, ..EvalConfig::default() }
+    }
+
+    fn limit_of(result: Result<RuntimeValue, RuntimeError>) -> Option<Limit> {
+        result.expect_err("the limit should stop the script").limit
+    }
+
+    #[test]
+    fn test_infinite_loop_is_stopped() {
+        let err = eval_both("while (1) {}", &under(Limits { max_steps: Some(10_000), ..Limits::default() })).unwrap_err();
+        assert_eq!(err.limit, Some(Limit::Steps));
+        assert_eq!(err.message, "Step limit of 10000 exceeded");
+        let timeout = under(Limits { timeout: Some(Duration::from_millis(50)), ..Limits::default() });
+        assert_eq!(limit_of(eval_both("while (1) {}", &timeout)), Some(Limit::Timeout));
+
+        let counted = "let mut i = 0\nwhile (i < 10) { i = i + 1 }";
+        eval_both(counted, &under(Limits { max_steps: Some(10_000), ..Limits::default() })).unwrap();
+    }
+
+    #[test]
+    fn test_value_size_is_capped() {
+        let source = "let mut s = \"ab\"\nwhile (1) { s = s + s }";
+        let err = eval_both(source, &under(Limits { max_value_size: Some(1000), ..Limits::default() })).unwrap_err();
+        assert_eq!(err.limit, Some(Limit::ValueSize));
+        assert_eq!(err.message, "Value size limit of 1000 bytes exceeded: 1024 bytes");
+
+        assert_eq!(limit_of(eval("fn F() { F() }\nF()")), Some(Limit::CallDepth));
+        assert_eq!(RuntimeError::new("plain").limit, None);
+    }
+
+    #[test]
+    fn test_struct_fields_count_towards_value_size() {
+        let source = "
+struct Box { label: string, items: list }
+let mut s = \"abcd\"
+s = s + s
+let b = Box(s, [s, s])
+";
+        let err = eval_both(source, &under(Limits { max_value_size: Some(20), ..Limits::default() })).unwrap_err();
+        assert_eq!(err.limit, Some(Limit::ValueSize));
+        assert_eq!(err.message, "Value size limit of 20 bytes exceeded: 24 bytes");
+
+        // Структура, що посилається сама на себе, рахується один раз
+        let cyclic = "
+struct Node { name: string, next: list }
+let n = Node(\"ab\", [])
+n.next = [n]
+let pair = [n, n]
+";
+        eval_both(cyclic, &under(Limits { max_value_size: Some(4), ..Limits::default() })).unwrap();
+    }
+
+    #[test]
+    fn test_unreachable_deadline_means_no_timeout() {
+        let source = "let mut i = 0\nwhile (i < 2000) { i = i + 1 }";
+        eval_both(source, &under(Limits { timeout: Some(Duration::MAX), ..Limits::default() })).unwrap();
+    }
+}
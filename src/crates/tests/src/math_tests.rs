@@ -0,0 +1,37 @@
+#[cfg(test)]
+mod tests {
+    use lexer::ast::AstParser;
+    use interpreter::checker::TypeChecker;
+    use crate::test_helper::*;
+
+    fn number(source: &str) -> f64 {
+        f64::try_from(eval(source).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_math_natives() {
+        assert_eq!(number("let x = sqrt(16)"), 4.0);
+        assert_eq!(number("let x = pow(2, 10)"), 1024.0);
+        assert_eq!(number("let x = abs(0 - 3.5)"), 3.5);
+        assert_eq!(number("let x = floor(2.7) + ceil(2.2)"), 5.0);
+        assert_eq!(number("let x = round(2.5) - round(1.4)"), 2.0);
+        assert_eq!(number("let x = min(4, 2, 8) * max(1, 7)"), 14.0);
+
+        assert_eq!(eval("let x = sqrt(0 - 4)").unwrap_err().message, "Cannot take the square root of negative number -4");
+        assert_eq!(eval("let x = max()").unwrap_err().message, "Function 'max' expects at least 1 argument, got 0");
+        let errors = TypeChecker::new().check(&AstParser::new(lex("let x: int = sqrt(4)\nlet y: int = round(2.5)")).parse().unwrap()).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message, "Variable 'x' is declared as Int, got Float");
+    }
+
+    #[test]
+    fn test_random_is_seeded() {
+        let source = "let a = random()\nlet x = random() + a";
+        // eval_both already checks that both engines draw the same numbers
+        let seeded = |seed| f64::try_from(eval_both(source, &EvalConfig { seed, ..EvalConfig::default() }).unwrap());
+        let first = seeded(42).unwrap();
+        assert!((0.0..2.0).contains(&first));
+        assert_eq!(seeded(42), Ok(first));
+        assert_ne!(seeded(43), Ok(first));
+    }
+}
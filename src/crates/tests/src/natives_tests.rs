@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod tests {
+    use lexer::ast::{AstParser, DataType, Stmt};
+    use lexer::tokenizer::Token;
+    use interpreter::checker::TypeChecker;
+    use interpreter::interpreter::RuntimeValue;
+    use interpreter::natives::{Arity, Native, Natives};
+    use interpreter::permissions::Permission;
+    use crate::test_helper::*;
+
+    const DOUBLE: u32 = 390;
+    const SECRET: u32 = 391;
+
+    fn natives(seed: u64) -> Natives {
+        let mut natives = Natives::with_seed(seed);
+        natives.register(
+            DOUBLE,
+            Native::new("double", Arity::Exact(1), |_, args| match &args[0] {
+                RuntimeValue::Number(n) => Ok(RuntimeValue::Number(n * 2.0)),
+                _ => unreachable!(),
+            })
+            .param("n", Some(DataType::Float))
+            .returns(DataType::Float),
+        );
+        natives.register(
+            SECRET,
+            Native::new("secret", Arity::Exact(0), |_, _| Ok(RuntimeValue::String("42".to_string())))
+                .needs(Permission::Env),
+        );
+        natives
+    }
+
+    /// Parses `source` with `double` and `secret` lexed as native functions.
+    fn parse(source: &str) -> Vec<Stmt> {
+        let tokens: Vec<Token> = lex(source).into_iter().map(|token| match token.value.as_str() {
+            "double" => Token { std_token_id: DOUBLE, ..token },
+            "secret" => Token { std_token_id: SECRET, ..token },
+            _ => token,
+        }).collect();
+        AstParser::new(tokens).parse().unwrap()
+    }
+
+    #[test]
+    fn test_registered_native_is_called() {
+        let config = EvalConfig { natives, ..EvalConfig::default() };
+        assert_eq!(eval_parsed(parse("let x = double(21)"), &config), Ok(RuntimeValue::Number(42.0)));
+        assert_eq!(
+            eval_parsed(parse("let x = double(\"a\")"), &config).unwrap_err().message,
+            "Argument 'n' of 'double' expects Float, got String"
+        );
+        assert_eq!(
+            eval_parsed(parse("let x = double(1, 2)"), &config).unwrap_err().message,
+            "Function 'double' expects 1 argument, got 2"
+        );
+        assert_eq!(
+            eval_parsed(parse("let x = secret()"), &config).unwrap_err().message,
+            "Permission denied: 'secret' needs env access, run with --allow-env"
+        );
+        assert_eq!(
+            eval_parsed(parse("double(1)"), &EvalConfig::default()).unwrap_err().message,
+            "Unknown native function 'double'"
+        );
+    }
+
+    #[test]
+    fn test_checker_uses_native_signatures() {
+        let mut checker = TypeChecker::new();
+        checker.natives = natives(0);
+        let errors = checker.check(&parse("let x: int = double(2)\nlet y = double(\"a\")\nlet z = len()")).unwrap_err();
+        let messages: Vec<&str> = errors.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, [
+            "Variable 'x' is declared as Int, got Float",
+            "Argument 'n' of 'double' expects Float, got String",
+            "'len' expects 1 argument, got 0",
+        ]);
+
+        let errors = TypeChecker::new().check(&parse("double(1)")).unwrap_err();
+        assert_eq!(errors[0].message, "Unknown native function 'double'");
+    }
+}
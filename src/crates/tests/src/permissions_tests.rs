@@ -0,0 +1,56 @@
+#[cfg(test)]
+mod tests {
+    use interpreter::interpreter::RuntimeValue;
+    use interpreter::permissions::{Permission, Permissions};
+    use crate::test_helper::*;
+
+    fn with(permissions: Permissions) -> EvalConfig<'static> {
+        EvalConfig { permissions, ..EvalConfig::default() }
+    }
+
+    #[test]
+    fn test_console_natives_need_permission() {
+        assert_eq!(
+            eval_both("let x = 1\nprint(x)", &with(Permissions::none())).unwrap_err().message,
+            "Permission denied: 'print' needs output access, it is disabled by --deny-output"
+        );
+        assert_eq!(
+            eval_both("let name = input(\"? \")", &with(Permissions::default().deny(Permission::Input))).unwrap_err().message,
+            "Permission denied: 'input' needs input access, it is disabled by --deny-input"
+        );
+        let config = with(Permissions::default());
+        eval_both("print(\"ok\")", &config).unwrap();
+        assert_eq!(config.output.contents(), "ok\n");
+    }
+
+    #[test]
+    fn test_default_permissions() {
+        let defaults = Permissions::default();
+        assert!(defaults.is_allowed(Permission::Input));
+        assert!(defaults.is_allowed(Permission::Output));
+        for permission in [Permission::Read, Permission::Write, Permission::Env] {
+            assert!(!defaults.is_allowed(permission));
+            assert!(Permissions::all().is_allowed(permission));
+        }
+        assert_eq!(defaults.allow(Permission::Read).deny(Permission::Read), Permissions::default());
+    }
+
+    #[test]
+    fn test_environment_needs_allow_env() {
+        assert_eq!(
+            eval_both("let x = get_env(\"CARGO_PKG_NAME\")", &with(Permissions::default())).unwrap_err().message,
+            "Permission denied: 'get_env' needs env access, run with --allow-env"
+        );
+
+        let allowed = with(Permissions::default().allow(Permission::Env));
+        let source = "let x = get_env(\"CARGO_PKG_NAME\") + \" \" + get_env(\"USQLREPL_SURELY_UNSET\", \"типово\")";
+        assert_eq!(
+            eval_both(source, &allowed),
+            Ok(RuntimeValue::String(format!("{} типово", env!("CARGO_PKG_NAME"))))
+        );
+        assert_eq!(
+            eval_both("let x = get_env(\"USQLREPL_SURELY_UNSET\")", &allowed).unwrap_err().message,
+            "Environment variable 'USQLREPL_SURELY_UNSET' is not set"
+        );
+    }
+}
@@ -0,0 +1,124 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use lexer::ast::AstParser;
+    use lexer::glossary::Glossary;
+    use interpreter::interpreter::{Interpreter, RuntimeValue};
+    use interpreter::resolver::{FunctionDecl, ResolvedExpr, ResolvedStmt, Resolver, Slot};
+    use crate::test_helper::*;
+
+    fn slot(stmt: &ResolvedStmt) -> &Slot {
+        match stmt {
+            ResolvedStmt::Expression(ResolvedExpr::Variable { slot, .. }) => slot,
+            other => panic!("expected a variable, got {:?}", other),
+        }
+    }
+
+    fn function(stmt: &ResolvedStmt) -> &FunctionDecl {
+        match stmt {
+            ResolvedStmt::Function { decl, .. } => decl,
+            other => panic!("expected a function, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_identifiers_bind_to_depth_and_index() {
+        let statements = AstParser::new(lex("
+let g = 1
+if (g) {
+    let a = 1
+    let b = 2
+    while (a) {
+        let a = 3
+        a
+        b
+        g
+    }
+}
+")).parse().unwrap();
+        let mut globals = vec!["earlier".to_string()];
+        let mut free_names = HashSet::new();
+        let resolved = Resolver::new(&Glossary::default(), &mut globals, &mut free_names).resolve(&statements);
+        assert_eq!(globals, ["earlier", "g"]);
+        assert!(free_names.is_empty());
+
+        let ResolvedStmt::If { then_branch, .. } = &resolved[1] else { panic!("expected if") };
+        assert_eq!(*then_branch.locals, ["a", "b"]);
+        let ResolvedStmt::While { body, .. } = &then_branch.statements[2] else { panic!("expected while") };
+        assert_eq!(*body.locals, ["a"]);
+        assert!(matches!(slot(&body.statements[1]), Slot::Local { depth: 0, index: 0 }));
+        assert!(matches!(slot(&body.statements[2]), Slot::Local { depth: 1, index: 1 }));
+        assert!(matches!(slot(&body.statements[3]), Slot::Global(1)));
+    }
+
+    #[test]
+    fn test_free_names_are_looked_up_in_callers() {
+        let statements = AstParser::new(lex("
+fn Outer(int n) -> int {
+    fn Inner() -> int { return n + g }
+    return Inner()
+}
+")).parse().unwrap();
+        let mut globals = Vec::new();
+        let mut free_names = HashSet::new();
+        let resolved = Resolver::new(&Glossary::default(), &mut globals, &mut free_names).resolve(&statements);
+        assert_eq!(free_names, HashSet::from(["n".to_string(), "g".to_string()]));
+        assert_eq!(globals, ["n", "g", "Outer"]);
+
+        let outer = function(&resolved[0]);
+        assert_eq!(*outer.locals, ["n", "Inner"]);
+        let inner = function(&outer.body[0]);
+        let ResolvedStmt::Return(Some(ResolvedExpr::Binary { left, right, .. })) = &inner.body[0] else { panic!("expected return") };
+        assert!(matches!(&**left, ResolvedExpr::Variable { slot: Slot::Dynamic { name, slot: 0 }, .. } if name == "n"));
+        assert!(matches!(&**right, ResolvedExpr::Variable { slot: Slot::Dynamic { name, slot: 1 }, .. } if name == "g"));
+    }
+
+    #[test]
+    fn test_functions_see_caller_locals() {
+        // Як і до резолвера: функція бачить змінні тих, хто її викликав
+        let mut interp = Interpreter::new();
+        interp.interpret(AstParser::new(lex("
+fn Peek() -> int { return hidden }
+fn Caller() -> int {
+    let hidden = 1
+    return Peek()
+}
+fn Outer() -> int {
+    let mut total = 1
+    fn Add(int n) { total = total + n }
+    Add(2)
+    Add(3)
+    return total
+}
+let mut result = Caller() * 10 + Outer()
+if (1) {
+    let step = 100
+    fn Step() -> int { return step }
+    result = result + Step()
+}
+")).parse().unwrap()).unwrap();
+        assert_eq!(interp.global("result"), Some(RuntimeValue::Number(116.0)));
+
+        interp.interpret(AstParser::new(lex("
+fn Outer(int n) -> int {
+    fn Twice(int x) -> int { return x * 2 }
+    fn Apply(int x) -> int { return Twice(x) + 1 }
+    return Apply(n)
+}
+let hidden = 5
+let result = Outer(hidden) + Peek()
+")).parse().unwrap()).unwrap();
+        assert_eq!(interp.global("result"), Some(RuntimeValue::Number(16.0)));
+
+        let err = interp.interpret(AstParser::new(lex("
+fn Caller() -> int {
+    let fixed = 1
+    fn Change() { fixed = 2 }
+    Change()
+    return fixed
+}
+let changed = Caller()
+")).parse().unwrap()).unwrap_err();
+        assert_eq!(err.message, "Cannot assign twice to immutable variable 'fixed'");
+    }
+}
@@ -0,0 +1,47 @@
+#[cfg(test)]
+mod tests {
+    use lexer::ast::AstParser;
+    use interpreter::checker::TypeChecker;
+    use interpreter::interpreter::RuntimeValue;
+    use crate::test_helper::*;
+
+    fn string(value: &str) -> RuntimeValue {
+        RuntimeValue::String(value.to_string())
+    }
+
+    #[test]
+    fn test_length_and_indexing_count_graphemes() {
+        assert_eq!(eval("let x = len(\"Їжачок\")"), Ok(RuntimeValue::Number(6.0)));
+        // «й» з комбінованим знаком — один символ, хоч і два code point-и
+        assert_eq!(eval("let x = len(\"и\u{306}й\")"), Ok(RuntimeValue::Number(2.0)));
+        assert_eq!(eval("let x = \"Їжачок\"[0] + \"Їжачок\"[5]"), Ok(string("Їк")));
+        assert_eq!(eval("let x = [10, 20, 30][1] + len([1, 2])"), Ok(RuntimeValue::Number(22.0)));
+        assert_eq!(eval("let x = \"Їжачок\"[6]").unwrap_err().message, "Index 6 out of range for length 6");
+        assert_eq!(eval("let x = [1][0.5]").unwrap_err().message, "Index must be Int, got Number");
+    }
+
+    #[test]
+    fn test_string_natives() {
+        assert_eq!(eval("let x = substring(\"Привіт, світ\", 8)"), Ok(string("світ")));
+        assert_eq!(eval("let x = substring(\"Привіт\", 0 - 2, 3)"), Ok(string("При")));
+        assert_eq!(eval("let x = upper(\"їжак\") + lower(\"ҐАНОК\")"), Ok(string("ЇЖАКґанок")));
+        assert_eq!(eval("let x = trim(\"  так \")"), Ok(string("так")));
+        assert_eq!(eval("let x = join(split(\"a,b,c\", \",\"), \"-\")"), Ok(string("a-b-c")));
+        assert_eq!(eval("let x = len(split(\"Їжак\", \"\"))"), Ok(RuntimeValue::Number(4.0)));
+        assert_eq!(eval("let x = replace(\"мама мила\", \"ма\", \"па\")"), Ok(string("папа мила")));
+        assert_eq!(eval("let x = find(\"Їжачок\", \"чок\") + find(\"Їжачок\", \"я\")"), Ok(RuntimeValue::Number(2.0)));
+        assert_eq!(eval("let x = contains(\"Їжачок\", \"жач\")"), Ok(RuntimeValue::Bool(true)));
+        assert_eq!(eval("let x = contains([1, 2], 3)"), Ok(RuntimeValue::Bool(false)));
+        assert_eq!(eval("let x = len(5)").unwrap_err().message, "Argument 'value' of 'len' expects String or List, got Number");
+    }
+
+    #[test]
+    fn test_checker_knows_string_types() {
+        let ok = "let s: string = upper(\"a\")[0]\nlet xs: list = split(s, \"\")\nlet n: int = find(s, \"a\")";
+        assert!(TypeChecker::new().check(&AstParser::new(lex(ok)).parse().unwrap()).is_ok());
+
+        let errors = TypeChecker::new().check(&AstParser::new(lex("let a = 5[0]\nlet b = \"abc\"[\"1\"]")).parse().unwrap()).unwrap_err();
+        let messages: Vec<&str> = errors.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, ["Cannot index value of type Int", "Index must be Int, got String"]);
+    }
+}
@@ -1,4 +1,16 @@
-use lexer::tokenizer::{Token, std_ids};
+use std::collections::HashMap;
+use lexer::ast::{AstParser, DataType, Stmt};
+use lexer::tokenizer::{Token, Span, std_ids};
+use db::model::AllLexem;
+use interpreter::{casts, dates, env_natives, files, math, strings};
+use interpreter::console::{CapturedOutput, OutputSink, ScriptedInput};
+use interpreter::dates::DateNames;
+use interpreter::error::RuntimeError;
+use interpreter::interpreter::{Interpreter, RuntimeValue};
+use interpreter::limits::Limits;
+use interpreter::natives::Natives;
+use interpreter::permissions::Permissions;
+use interpreter::vm::Vm;
 
 pub fn t(value: &str, id: u32) -> Token {
     Token {
@@ -6,6 +18,8 @@ pub fn t(value: &str, id: u32) -> Token {
         token_type: "TestType".to_string(),
         std_token_id: id,
         lexem: value.to_string(),
+        span: Span::default(),
+        lang: None,
     }
 }
 
@@ -21,4 +35,258 @@ pub const ID_L_PAREN: u32 = std_ids::L_PAREN; // 3
 pub const ID_R_PAREN: u32 = std_ids::R_PAREN; // 4
 pub const ID_COLON: u32 = std_ids::COLON;     // 7
 pub const ID_COMMA: u32 = std_ids::COMMA;     // 9
-pub const ID_NUM: u32 = std_ids::INT_LITERAL; // 71 (або 72 для float)
\ No newline at end of file
+pub const ID_NUM: u32 = std_ids::INT_LITERAL; // 71 (або 72 для float)
+
+pub fn t_at(value: &str, id: u32, line: u32, column: u32) -> Token {
+    Token {
+        span: Span { line, column },
+        ..t(value, id)
+    }
+}
+
+pub fn t_lang(value: &str, id: u32, lang: &str) -> Token {
+    Token {
+        lang: Some(lang.to_string()),
+        ..t(value, id)
+    }
+}
+
+pub fn ws(value: &str) -> Token {
+    Token {
+        token_type: "Whitespace".to_string(),
+        ..t(value, 0)
+    }
+}
+
+pub fn lexem_row(id: u32, lang: &str, lexem: &str, type_info: Option<&str>, std_lexem: u32) -> AllLexem {
+    AllLexem {
+        id,
+        lang_name: lang.to_string(),
+        lexem: lexem.to_string(),
+        type_info: type_info.map(str::to_string),
+        std_lexem,
+    }
+}
+
+pub fn comment(value: &str) -> Token {
+    Token {
+        token_type: "Comment".to_string(),
+        ..t(value, 0)
+    }
+}
+
+/// Tokenizes English-keyword source without the lexicon database, for tests
+/// and benchmarks that need more than a handful of tokens.
+pub fn lex(source: &str) -> Vec<Token> {
+    let keyword = |word: &str| match word {
+        "let" => Some(std_ids::LET),
+        "mut" => Some(std_ids::MUT),
+        "fn" => Some(std_ids::FUNCTION),
+        "struct" => Some(std_ids::STRUCT),
+        "if" => Some(std_ids::IF),
+        "else" => Some(std_ids::ELSE),
+        "while" => Some(std_ids::WHILE),
+        "return" => Some(std_ids::RETURN),
+        "print" => Some(std_ids::PRINT),
+        "input" => Some(std_ids::INPUT),
+        "len" => Some(std_ids::LEN),
+        "sqrt" => Some(math::SQRT),
+        "pow" => Some(math::POW),
+        "abs" => Some(math::ABS),
+        "floor" => Some(math::FLOOR),
+        "ceil" => Some(math::CEIL),
+        "round" => Some(math::ROUND),
+        "min" => Some(math::MIN),
+        "max" => Some(math::MAX),
+        "random" => Some(math::RANDOM),
+        "substring" => Some(strings::SUBSTRING),
+        "split" => Some(strings::SPLIT),
+        "join" => Some(strings::JOIN),
+        "trim" => Some(strings::TRIM),
+        "upper" => Some(strings::UPPER),
+        "lower" => Some(strings::LOWER),
+        "contains" => Some(strings::CONTAINS),
+        "replace" => Some(strings::REPLACE),
+        "find" => Some(strings::FIND),
+        "to_int" => Some(casts::TO_INT),
+        "to_float" => Some(casts::TO_FLOAT),
+        "to_string" => Some(casts::TO_STRING),
+        "to_bool" => Some(casts::TO_BOOL),
+        "type_of" => Some(casts::TYPE_OF),
+        "read_file" => Some(files::READ_FILE),
+        "write_file" => Some(files::WRITE_FILE),
+        "append_file" => Some(files::APPEND_FILE),
+        "list_dir" => Some(files::LIST_DIR),
+        "exists" => Some(files::EXISTS),
+        "now" => Some(dates::NOW),
+        "format_date" => Some(dates::FORMAT_DATE),
+        "parse_date" => Some(dates::PARSE_DATE),
+        "date" => Some(dates::DATE),
+        "days" => Some(dates::DAYS),
+        "hours" => Some(dates::HOURS),
+        "add_months" => Some(dates::ADD_MONTHS),
+        "year" => Some(dates::YEAR),
+        "month" => Some(dates::MONTH),
+        "day" => Some(dates::DAY),
+        "get_env" => Some(env_natives::GET_ENV),
+        "int" => Some(std_ids::INT_TYPE),
+        "float" => Some(std_ids::FLOAT_TYPE),
+        "string" => Some(std_ids::STRING_TYPE),
+        "bool" => Some(std_ids::BOOL_TYPE),
+        "list" => Some(std_ids::LIST_TYPE),
+        "->" => Some(std_ids::ARROW),
+        "{" => Some(std_ids::L_BRACE),
+        "}" => Some(std_ids::R_BRACE),
+        "(" => Some(std_ids::L_PAREN),
+        ")" => Some(std_ids::R_PAREN),
+        "[" => Some(std_ids::L_BRACKET),
+        "]" => Some(std_ids::R_BRACKET),
+        ":" => Some(std_ids::COLON),
+        "," => Some(std_ids::COMMA),
+        "." => Some(std_ids::DOT),
+        "=" => Some(std_ids::ASSIGN),
+        "+" => Some(std_ids::PLUS),
+        "-" => Some(std_ids::MINUS),
+        "*" => Some(std_ids::MULTIPLY),
+        "/" => Some(std_ids::DIVIDE),
+        "<" => Some(std_ids::LT),
+        ">" => Some(std_ids::GT),
+        _ => None,
+    };
+
+    let mut tokens = Vec::new();
+    for (line, text) in source.lines().enumerate() {
+        let chars: Vec<char> = text.chars().collect();
+        let mut i = 0;
+        while i < chars.len() {
+            let start = i;
+            let c = chars[i];
+            let at = |value: &str, id: u32| t_at(value, id, line as u32 + 1, start as u32 + 1);
+            if c.is_whitespace() {
+                i += 1;
+                continue;
+            }
+            if c == '"' {
+                let end = (i + 1..chars.len()).find(|&j| chars[j] == '"').expect("unterminated string");
+                let content: String = chars[i + 1..end].iter().collect();
+                tokens.push(Token { lexem: content, ..at(&chars[i..=end].iter().collect::<String>(), std_ids::STRING_LITERAL) });
+                i = end + 1;
+                continue;
+            }
+            if c == '-' && chars.get(i + 1) == Some(&'>') {
+                tokens.push(at("->", std_ids::ARROW));
+                i += 2;
+                continue;
+            }
+            if !c.is_alphanumeric() && c != '_' {
+                let symbol = c.to_string();
+                tokens.push(at(&symbol, keyword(&symbol).expect("unknown symbol")));
+                i += 1;
+                continue;
+            }
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '.' && chars[start].is_numeric()) {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            let id = if word.chars().next().unwrap().is_numeric() {
+                if word.contains('.') { std_ids::FLOAT_LITERAL } else { std_ids::INT_LITERAL }
+            } else {
+                keyword(&word).unwrap_or(std_ids::IDENTIFIER)
+            };
+            tokens.push(at(&word, id));
+        }
+    }
+    tokens
+}
+
+/// How `eval_both` sets up the two engines.
+#[derive(Clone)]
+pub struct EvalConfig<'a> {
+    /// Lines `input` answers with.
+    pub input: &'a [&'a str],
+    pub type_names: HashMap<DataType, String>,
+    pub date_names: DateNames,
+    pub permissions: Permissions,
+    pub limits: Limits,
+    /// Seed of `random`, the same in both engines.
+    pub seed: u64,
+    /// Builds the natives of one engine from `seed`.
+    pub natives: fn(u64) -> Natives,
+    /// Called before each engine runs, to undo what the other one left
+    /// behind (e.g. files its script wrote).
+    pub reset: Option<&'a dyn Fn()>,
+    /// Receives what the script printed, which both engines must print alike.
+    pub output: CapturedOutput,
+    /// Globals besides `x` the engines must agree on.
+    pub globals: &'a [&'a str],
+}
+
+impl Default for EvalConfig<'_> {
+    fn default() -> Self {
+        EvalConfig {
+            input: &[],
+            type_names: HashMap::new(),
+            date_names: DateNames::default(),
+            permissions: Permissions::default(),
+            limits: Limits::default(),
+            seed: 0,
+            natives: Natives::with_seed,
+            reset: None,
+            output: CapturedOutput::new(),
+            globals: &[],
+        }
+    }
+}
+
+/// Runs `source` on the interpreter and the VM, checks they agree on the
+/// outcome, on what they print, on `x` and on `config.globals`, and returns
+/// the value of `x` (`Void` when the script does not define it).
+pub fn eval_both(source: &str, config: &EvalConfig) -> Result<RuntimeValue, RuntimeError> {
+    eval_parsed(AstParser::new(lex(source)).parse().unwrap(), config)
+}
+
+/// `eval_both` for statements parsed elsewhere.
+pub fn eval_parsed(statements: Vec<Stmt>, config: &EvalConfig) -> Result<RuntimeValue, RuntimeError> {
+    let reset = || {
+        if let Some(reset) = config.reset {
+            reset();
+        }
+    };
+
+    reset();
+    let interp_output = CapturedOutput::new();
+    let mut interp = Interpreter::new();
+    interp.type_names = config.type_names.clone();
+    interp.date_names = config.date_names.clone();
+    interp.permissions = config.permissions.clone();
+    interp.limits = config.limits.clone();
+    interp.natives = (config.natives)(config.seed);
+    interp.output = Box::new(interp_output.clone());
+    interp.input = Box::new(ScriptedInput::new(config.input.iter().copied()));
+    let expected = interp.interpret(statements.clone());
+
+    reset();
+    let vm_output = CapturedOutput::new();
+    let mut vm = Vm::new();
+    vm.type_names = config.type_names.clone();
+    vm.date_names = config.date_names.clone();
+    vm.permissions = config.permissions.clone();
+    vm.limits = config.limits.clone();
+    vm.natives = (config.natives)(config.seed);
+    vm.output = Box::new(vm_output.clone());
+    vm.input = Box::new(ScriptedInput::new(config.input.iter().copied()));
+    let actual = vm.interpret(statements.clone());
+
+    assert_eq!(actual, expected, "engines disagree on:\n{:#?}", statements);
+    assert_eq!(vm_output.contents(), interp_output.contents(), "engines print differently:\n{:#?}", statements);
+    for name in std::iter::once(&"x").chain(config.globals) {
+        assert_eq!(vm.global(name), interp.global(name), "global '{}' of:\n{:#?}", name, statements);
+    }
+    config.output.clone().write(&interp_output.contents()).expect("captured output never fails");
+    expected.map(|_| interp.global("x").unwrap_or(RuntimeValue::Void))
+}
+
+/// `eval_both` with the default setup.
+pub fn eval(source: &str) -> Result<RuntimeValue, RuntimeError> {
+    eval_both(source, &EvalConfig::default())
+}
@@ -14,13 +14,13 @@ mod tests {
         ];
 
         let mut parser = AstParser::new(tokens);
-        let result = parser.parse();
+        let result = parser.parse().unwrap();
 
         assert_eq!(result.len(), 1);
         
-        if let Stmt::VariableDeclaration { name, value } = &result[0] {
+        if let Stmt::VariableDeclaration { name, value, .. } = &result[0] {
             assert_eq!(name, "x");
-            if let Expr::Number(val) = value {
+            if let Some(Expr::Number(val)) = value {
                 assert_eq!(*val, 10.0);
             } else {
                 panic!("Expected Number value");
@@ -30,6 +30,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_typed_mutable_declaration_and_assignment() {
+        let tokens = vec![
+            t("let", ID_VAR),
+            t("mut", std_ids::MUT),
+            t("x", ID_NAME),
+            t(":", ID_COLON),
+            t("ціле", ID_INT),
+            t("=", ID_EQ),
+            t("5", ID_NUM),
+            t("x", ID_NAME),
+            t("=", ID_EQ),
+            t("6", ID_NUM),
+        ];
+
+        let mut parser = AstParser::new(tokens);
+        let result = parser.parse().unwrap();
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0], Stmt::VariableDeclaration {
+            name: "x".to_string(),
+            mutable: true,
+            data_type: Some(DataType::Int),
+            value: Some(Expr::Number(5.0)),
+            span: Default::default(),
+        });
+        if let Stmt::Assignment { name, value, .. } = &result[1] {
+            assert_eq!(name, "x");
+            assert_eq!(*value, Expr::Number(6.0));
+        } else {
+            panic!("Expected Assignment");
+        }
+    }
+
     #[test]
     fn test_struct_declaration() {
         let tokens = vec![
@@ -43,11 +77,11 @@ mod tests {
         ];
 
         let mut parser = AstParser::new(tokens);
-        let result = parser.parse();
+        let result = parser.parse().unwrap();
 
         assert_eq!(result.len(), 1);
 
-        if let Stmt::StructDeclaration { name, fields } = &result[0] {
+        if let Stmt::StructDeclaration { name, fields, .. } = &result[0] {
             assert_eq!(name, "User");
             assert_eq!(fields.len(), 1);
             assert_eq!(fields[0].0, "id");
@@ -71,10 +105,11 @@ mod tests {
         ];
 
         let mut parser = AstParser::new(tokens);
-        let result = parser.parse();
+        let result = parser.parse().unwrap();
 
-        if let Stmt::FunctionDeclaration { name, params, body } = &result[0] {
+        if let Stmt::FunctionDeclaration { name, params, return_type, body, .. } = &result[0] {
             assert_eq!(name, "main");
+            assert_eq!(*return_type, None);
             assert_eq!(params.len(), 1);
             assert_eq!(params[0].0, "arg");
             assert_eq!(params[0].1, DataType::Float);
@@ -84,6 +119,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_function_return_type() {
+        let tokens = vec![
+            t("fn", ID_FUNC),
+            t("Factorial", ID_NAME),
+            t("(", ID_L_PAREN),
+            t("ціле", ID_INT),
+            t("n", ID_NAME),
+            t(")", ID_R_PAREN),
+            t("->", std_ids::ARROW),
+            t("ціле", ID_INT),
+            t("{", ID_L_BRACE),
+            t("}", ID_R_BRACE),
+        ];
+
+        let mut parser = AstParser::new(tokens);
+        let result = parser.parse().unwrap();
+
+        if let Stmt::FunctionDeclaration { return_type, .. } = &result[0] {
+            assert_eq!(*return_type, Some(DataType::Int));
+        } else {
+            panic!("Expected FunctionDeclaration");
+        }
+    }
+
     #[test]
     fn test_complex_integration() {
         let tokens = vec![
@@ -111,12 +171,12 @@ mod tests {
         ];
 
         let mut parser = AstParser::new(tokens);
-        let statements = parser.parse();
+        let statements = parser.parse().unwrap();
 
         assert_eq!(statements.len(), 2);
 
         match &statements[0] {
-            Stmt::StructDeclaration { name, fields } => {
+            Stmt::StructDeclaration { name, fields, .. } => {
                 assert_eq!(name, "Test");
                 assert_eq!(fields[0].0, "user_id");
                 assert_eq!(fields[0].1, DataType::Int);
@@ -125,7 +185,7 @@ mod tests {
         }
 
         match &statements[1] {
-            Stmt::FunctionDeclaration { name, params, body } => {
+            Stmt::FunctionDeclaration { name, params, body, .. } => {
                 assert_eq!(name, "Func");
                 
                 assert_eq!(params.len(), 1);
@@ -137,10 +197,10 @@ mod tests {
 
                 assert_eq!(body.len(), 1);
                 match &body[0] {
-                    Stmt::VariableDeclaration { name, value } => {
+                    Stmt::VariableDeclaration { name, value, .. } => {
                         assert_eq!(name, "myVariable");
                         match value {
-                            Expr::Identifier(val_name) => assert_eq!(val_name, "Дані"),
+                            Some(Expr::Identifier { name: val_name, .. }) => assert_eq!(val_name, "Дані"),
                             _ => panic!("Значення змінної має бути ідентифікатором"),
                         }
                     },
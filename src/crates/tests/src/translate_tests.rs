@@ -0,0 +1,86 @@
+#[cfg(test)]
+mod tests {
+    use lexer::tokenizer::std_ids;
+    use db::model::GlossaryEntry;
+    use lexer::glossary::Glossary;
+    use lexer::translate::Translator;
+    use crate::test_helper::*;
+
+    #[test]
+    fn test_translate_keywords_keeps_everything_else() {
+        let english = vec![
+            lexem_row(119, "EN", "function", None, std_ids::FUNCTION),
+            lexem_row(123, "EN", "int_literal", None, std_ids::INT_LITERAL),
+            lexem_row(228, "EN", "int", Some("Int"), std_ids::INT_TYPE),
+            lexem_row(241, "EN", "print", None, std_ids::PRINT),
+            lexem_row(75, "EN", "(", None, std_ids::L_PAREN),
+            lexem_row(76, "EN", ")", None, std_ids::R_PAREN),
+        ];
+
+        // функція Show(ціле n) // коментар\n  друк("ціле", n)
+        let tokens = vec![
+            t_lang("функція", ID_FUNC, "UA"),
+            ws(" "),
+            t("Show", ID_NAME),
+            t_lang("(", ID_L_PAREN, "UA"),
+            t_lang("ціле", ID_INT, "UA"),
+            ws(" "),
+            t("n", ID_NAME),
+            t_lang(")", ID_R_PAREN, "UA"),
+            ws(" "),
+            comment("// коментар"),
+            ws("\n  "),
+            t_lang("друк", std_ids::PRINT, "UA"),
+            t_lang("(", ID_L_PAREN, "UA"),
+            t("\"ціле\"", std_ids::STRING_LITERAL),
+            t_lang(",", ID_COMMA, "UA"),
+            ws(" "),
+            t("n", ID_NAME),
+            t_lang(")", ID_R_PAREN, "UA"),
+        ];
+
+        let output = Translator::new("EN", &english, Glossary::default()).translate(&tokens);
+
+        // Кома не має EN рядка в цьому словнику, тому лишається як є
+        assert_eq!(output, "function Show(int n) // коментар\n  print(\"ціле\", n)");
+    }
+
+    fn glossary() -> Glossary {
+        let entry = |id, lang: &str, identifier: &str, canonical: &str| GlossaryEntry {
+            id,
+            lang_name: lang.to_string(),
+            identifier: identifier.to_string(),
+            canonical: canonical.to_string(),
+        };
+        Glossary::new(&[
+            entry(1, "EN", "animal", "animal"),
+            entry(2, "UA", "Тварина", "animal"),
+        ])
+    }
+
+    #[test]
+    fn test_glossary_maps_aliases_to_canonical() {
+        let glossary = glossary();
+        assert_eq!(glossary.canonical("Тварина"), "animal");
+        assert_eq!(glossary.canonical("other"), "other");
+        assert_eq!(glossary.spelling("Тварина", "EN"), Some("animal"));
+        assert_eq!(glossary.spelling("animal", "UA"), Some("Тварина"));
+        assert_eq!(glossary.spelling("other", "UA"), None);
+    }
+
+    #[test]
+    fn test_translate_renames_glossary_identifiers() {
+        let english = vec![lexem_row(119, "EN", "function", None, std_ids::FUNCTION)];
+        let tokens = vec![
+            t_lang("функція", ID_FUNC, "UA"),
+            ws(" "),
+            t("Тварина", std_ids::IDENTIFIER),
+            ws(" "),
+            t("Кіт", std_ids::IDENTIFIER),
+        ];
+
+        let output = Translator::new("EN", &english, glossary()).translate(&tokens);
+
+        assert_eq!(output, "function animal Кіт");
+    }
+}
@@ -0,0 +1,312 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use lexer::ast::{AstParser, DataType};
+    use lexer::tokenizer::Span;
+    use interpreter::interpreter::{Interpreter, RuntimeValue};
+    use interpreter::limits::Limits;
+    use interpreter::vm::Vm;
+    use crate::test_helper::*;
+
+    #[test]
+    fn test_vm_runs_recursion_and_loops() {
+        let source = "
+fn Factorial(int n) -> int {
+    if (n < 2) { return 1 }
+    return n * Factorial(n - 1)
+}
+let fact = Factorial(10)
+let mut sum = 0
+let mut i = 0
+while (i < 100) {
+    let step = i * 2
+    sum = sum + step
+    i = i + 1
+}
+";
+        eval_both(source, &EvalConfig { globals: &["fact", "sum", "i"], ..EvalConfig::default() }).unwrap();
+
+        let mut vm = Vm::new();
+        vm.interpret(AstParser::new(lex(source)).parse().unwrap()).unwrap();
+        assert_eq!(vm.global("fact"), Some(RuntimeValue::Number(3628800.0)));
+        assert_eq!(vm.global("sum"), Some(RuntimeValue::Number(9900.0)));
+    }
+
+    #[test]
+    fn test_vm_structs_blocks_and_shadowing() {
+        eval_both("
+struct Point { x: int, y: int }
+let p = Point(1, 2)
+let x = p.x + p.y
+let mut outer = 1
+if (x > 2) {
+    let outer = 10
+    let x = outer + 1
+    print(x, p)
+} else {
+    outer = 5
+}
+fn Local() {
+    fn Inner(int n) -> int {
+        if (n < 1) { return 0 }
+        return Inner(n - 1) + 1
+    }
+    return Inner(3)
+}
+let local = Local()
+if (1) { return 5 let skipped = 1 }
+let after = 1
+", &EvalConfig { globals: &["outer", "local", "skipped", "after"], ..EvalConfig::default() }).unwrap();
+    }
+
+    #[test]
+    fn test_declarations_without_initializer_start_at_zero_of_their_type() {
+        let source = "
+let n
+let f: float
+let s: string
+let b: bool
+let l: list
+let described = s + \"!\"
+";
+        eval_both(source, &EvalConfig { globals: &["n", "f", "s", "b", "l", "described"], ..EvalConfig::default() }).unwrap();
+
+        let mut vm = Vm::new();
+        vm.interpret(AstParser::new(lex(source)).parse().unwrap()).unwrap();
+        assert_eq!(vm.global("s"), Some(RuntimeValue::String(String::new())));
+        assert_eq!(vm.global("b"), Some(RuntimeValue::Bool(false)));
+        assert_eq!(vm.global("l"), Some(RuntimeValue::List(Default::default())));
+    }
+
+    #[test]
+    fn test_struct_declaration_without_initializer_is_rejected() {
+        let error = AstParser::new(lex("struct P { x: int }\nlet p: P")).parse().unwrap_err();
+        assert_eq!(error.message, "Expect '=': variable 'p' of struct type 'P' needs an initial value");
+        assert_eq!(error.span, Span { line: 2, column: 8 });
+
+        let error = AstParser::new(lex("let x = (1 + ")).parse().unwrap_err();
+        assert_eq!(error.to_string(), "Syntax error at 1:12: Expect expression.");
+    }
+
+    #[test]
+    fn test_tail_calls_return_from_nested_blocks_and_loops() {
+        let source = "
+fn Seven() -> int { return 7 }
+fn FromIf(int n) -> int {
+    if (n > 0) {
+        return Seven()
+        let skipped = 1
+    }
+    return 99
+}
+fn FromWhile() -> int {
+    let mut i = 0
+    while (i < 10) {
+        i = i + 1
+        if (i > 2) { return Seven() }
+    }
+    return 99
+}
+fn Count(int n, int acc) -> int {
+    while (1) {
+        if (n < 1) { return acc }
+        return Count(n - 1, acc + 1)
+    }
+    return 99
+}
+let from_if = FromIf(1)
+let from_while = FromWhile()
+let counted = Count(100000, 0)
+";
+        eval_both(source, &EvalConfig { globals: &["from_if", "from_while", "counted"], ..EvalConfig::default() }).unwrap();
+
+        let mut interp = Interpreter::new();
+        interp.interpret(AstParser::new(lex(source)).parse().unwrap()).unwrap();
+        assert_eq!(interp.global("from_if"), Some(RuntimeValue::Number(7.0)));
+        assert_eq!(interp.global("from_while"), Some(RuntimeValue::Number(7.0)));
+        assert_eq!(interp.global("counted"), Some(RuntimeValue::Number(100000.0)));
+    }
+
+    #[test]
+    fn test_engines_agree_on_free_variables() {
+        // Функція бачить глобальні змінні на момент виклику та функції навколо себе
+        eval_both("
+let mut counter = 1
+fn Read() -> int { return counter }
+let before = Read()
+counter = 5
+let after = Read()
+fn Outer(int n) -> int {
+    fn Twice(int m) -> int { return m * 2 }
+    fn Apply() -> int { return Twice(3) }
+    return Apply() + n
+}
+let applied = Outer(1)
+", &EvalConfig { globals: &["before", "after", "applied"], ..EvalConfig::default() }).unwrap();
+
+        // ...а решту імен шукає серед змінних тих, хто її викликав, як і до резолвера
+        let seen = eval_both("
+fn Outer() -> int {
+    let hidden = 1
+    fn Inner() -> int { return hidden }
+    return Inner()
+}
+fn Caller() -> int {
+    let local = 2
+    return Peek()
+}
+fn Peek() -> int { return local }
+let mut x = Outer() * 10 + Caller()
+if (1) {
+    let step = 100
+    fn Step() { x = x + step }
+    Step()
+}
+", &EvalConfig::default()).unwrap();
+        assert_eq!(seen, RuntimeValue::Number(112.0));
+
+        let err = eval("
+fn Caller() -> int {
+    let fixed = 1
+    fn Change() { fixed = 2 }
+    Change()
+    return fixed
+}
+let changed = Caller()
+").unwrap_err();
+        assert_eq!(err.message, "Cannot assign twice to immutable variable 'fixed'");
+        let err = eval("
+fn Caller() -> int {
+    let mut typed: int = 1
+    fn Change() { typed = \"s\" }
+    Change()
+    return typed
+}
+let changed = Caller()
+").unwrap_err();
+        assert_eq!(err.message, "Variable 'typed' is declared as Int, got String");
+    }
+
+    #[test]
+    fn test_vm_reports_errors_like_interpreter() {
+        let err = eval("
+fn Inner() { return missing }
+fn Outer() -> int { return 1 + Inner() }
+Outer()
+").unwrap_err();
+        assert_eq!(err.message, "Undefined variable 'missing'");
+        assert_eq!(err.span, Some(Span { line: 2, column: 21 }));
+        let frames: Vec<&str> = err.stack.iter().map(|f| f.function.as_str()).collect();
+        assert_eq!(frames, ["Inner", "Outer"]);
+
+        eval("fn F() -> int { return \"x\" }\nlet y = 1 + F()").unwrap_err();
+        eval_both("let fixed = 1\nfixed = 2", &EvalConfig { globals: &["fixed"], ..EvalConfig::default() }).unwrap_err();
+        eval("fn F() { let a = 1\na = 2 }\nF()").unwrap_err();
+        eval_both("let mut n: int = 1\nn = \"x\"", &EvalConfig { globals: &["n"], ..EvalConfig::default() }).unwrap_err();
+        eval("let s: string = 1").unwrap_err();
+        eval("fn F(int a) {}\nF(1, print(2))").unwrap_err();
+        eval("fn F(int a) {}\nF(\"x\")").unwrap_err();
+        eval("struct P { x: int }\nlet p = P(\"x\")").unwrap_err();
+        eval("struct P { x: int }\nlet p = P(1)\nlet q = p.y").unwrap_err();
+        eval("let n = 1\nlet q = n.y").unwrap_err();
+        eval("Missing(1)").unwrap_err();
+        eval("let l = len(1)").unwrap_err();
+        eval("let l = len(\"ab\", \"c\")").unwrap_err();
+        eval_both("let l = len(\"abc\")", &EvalConfig { globals: &["l"], ..EvalConfig::default() }).unwrap();
+    }
+
+    #[test]
+    fn test_assignment_errors_use_script_type_names() {
+        let type_names = HashMap::from([(DataType::Int, "ціле".to_string())]);
+        let config = EvalConfig { type_names, ..EvalConfig::default() };
+        for source in [
+            "let mut x: int = 1\nx = \"s\"",
+            "fn F() { let mut n: int = 1\nn = \"s\" }\nF()",
+            "fn F() { let mut n: int = 1\nG() }\nfn G() { n = \"s\" }\nF()",
+        ] {
+            let err = eval_both(source, &config).unwrap_err();
+            assert!(err.message.ends_with("is declared as ціле, got String"), "{}", err.message);
+        }
+    }
+
+    #[test]
+    fn test_struct_instances_are_shared_references() {
+        let source = "
+struct Counter { count: int, label: string }
+fn Bump(Counter c) { c.count = c.count + 1 }
+let first = Counter(0, \"a\")
+let alias = first
+Bump(first)
+Bump(alias)
+let mut n = first.count
+let copy = n
+n = 10
+let total = first.count + copy
+";
+        eval_both(source, &EvalConfig { globals: &["total", "n", "copy"], ..EvalConfig::default() }).unwrap();
+        let mut vm = Vm::new();
+        vm.interpret(AstParser::new(lex(source)).parse().unwrap()).unwrap();
+        assert_eq!(vm.global("total"), Some(RuntimeValue::Number(4.0)));
+        assert_eq!(vm.global("copy"), Some(RuntimeValue::Number(2.0)));
+
+        let err = eval("struct P { x: int }\nlet p = P(1)\np.x = \"s\"").unwrap_err();
+        assert_eq!(err.message, "Field 'x' of 'P' expects Int, got String");
+        eval("struct P { x: int }\nlet p = P(1)\np.y = 2").unwrap_err();
+        eval("let n = 1\nn.y = 2").unwrap_err();
+    }
+
+    #[test]
+    fn test_tail_calls_run_in_constant_stack() {
+        eval_both("
+fn Count(int n, int acc) -> int {
+    if (n < 1) { return acc }
+    return Count(n - 1, acc + 1)
+}
+fn IsEven(int n) -> int {
+    if (n < 1) { return 1 }
+    return IsOdd(n - 1)
+}
+fn IsOdd(int n) -> int {
+    if (n < 1) { return 0 }
+    return IsEven(n - 1)
+}
+let total = Count(20000, 0)
+let even = IsEven(5001)
+", &EvalConfig { globals: &["total", "even"], ..EvalConfig::default() }).unwrap();
+
+        // Тип результату заміненого виклику все одно перевіряється
+        let err = eval("
+fn Inner() -> string { return \"x\" }
+fn Outer() -> int { return Inner() }
+let v = Outer()
+").unwrap_err();
+        assert_eq!(err.message, "Function 'Outer' must return Int, got String");
+        let frames: Vec<&str> = err.stack.iter().map(|f| f.function.as_str()).collect();
+        assert_eq!(frames, ["Outer"]);
+    }
+
+    #[test]
+    fn test_call_depth_limit_is_a_runtime_error() {
+        let source = "
+fn Deep(int n) -> int {
+    if (n < 1) { return 0 }
+    return Deep(n - 1) + 1
+}
+let depth = Deep(5000)
+";
+        let err = eval(source).unwrap_err();
+        assert_eq!(err.message, "Stack overflow: maximum call depth of 1000 exceeded");
+        assert_eq!(err.stack.len(), 1000);
+        assert!(err.to_string().ends_with("in Deep (called at 4:12)\n    ... repeated 998 more time(s)\n    in Deep (called at 6:13)"), "{}", err);
+
+        let limits = Limits { max_call_depth: 6000, ..Limits::default() };
+        let mut interp = Interpreter::new();
+        interp.limits = limits.clone();
+        interp.interpret(AstParser::new(lex(source)).parse().unwrap()).unwrap();
+        assert_eq!(interp.global("depth"), Some(RuntimeValue::Number(5000.0)));
+        let mut vm = Vm::new();
+        vm.limits = limits;
+        vm.interpret(AstParser::new(lex(source)).parse().unwrap()).unwrap();
+        assert_eq!(vm.global("depth"), Some(RuntimeValue::Number(5000.0)));
+    }
+}
@@ -4,6 +4,7 @@ pub enum PrimitiveType {
     Int,
     Float,
     String,
+    Bool,
     Tuple,
     List,
     Dictionary,
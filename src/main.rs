@@ -1,24 +1,69 @@
 use dotenvy::dotenv;
 use tracing::{info, error};
 use std::env;
-use tokio;
-use clap::Parser;
+use clap::{CommandFactory, Parser, Subcommand, error::ErrorKind};
 use interpreter::interpreter::Interpreter;
-use shared::{logging, configuration::CONFIG};
-use lexer::{tokenizer, ast::AstParser};
+use interpreter::vm::Vm;
+use interpreter::checker::TypeChecker;
+use interpreter::limits::{DEFAULT_MAX_CALL_DEPTH, Limits};
+use interpreter::dates::DateNames;
+use interpreter::natives::Natives;
+use interpreter::permissions::{Permission, Permissions};
+use shared::{logging, configuration::{self, CONFIG}};
+use lexer::{tokenizer::{self, Token}, ast::{AstParser, DataType, ParseError, Stmt}, cache::{self, ProgramCache}, format, glossary::Glossary, serialize::{self, ParsedProgram}, translate::Translator};
 use db::db::DB;
 use std::fs;
+use std::collections::HashMap;
+use std::time::Duration;
+
+const EXIT_RUNTIME_ERROR: i32 = 1;
+const EXIT_TYPE_ERROR: i32 = 2;
+const EXIT_SYNTAX_ERROR: i32 = 2;
+const EXIT_LIMIT_EXCEEDED: i32 = 3;
+const EXIT_NOT_FORMATTED: i32 = 1;
+const EXIT_USAGE_ERROR: i32 = 64;
 
 #[derive(Parser, Debug)]
 #[command(name = "usqlrepl")]
 #[command(version, about = "Your REPL")]
 struct Args {
-    #[arg(long)]
+    #[arg(long, global = true)]
     no_logging: bool,
-    #[arg(long)]
+    #[arg(long, global = true)]
     lang: Option<String>,
-    #[arg(long)]
+    #[arg(long, global = true)]
     path: Option<String>,
+    #[arg(long, help = "Skip static type checking before execution")]
+    no_check: bool,
+    #[arg(long, help = "Always re-tokenize and re-parse, bypassing the program cache")]
+    no_cache: bool,
+    #[arg(long, global = true, help = "Run on the bytecode VM instead of the tree-walking interpreter")]
+    vm: bool,
+    #[arg(long, global = true, default_value_t = DEFAULT_MAX_CALL_DEPTH, help = "Maximum depth of nested function calls")]
+    max_depth: usize,
+    #[arg(long, global = true, help = "Stop the script after this many evaluation steps")]
+    max_steps: Option<u64>,
+    #[arg(long, global = true, help = "Largest string a script may build, in bytes")]
+    max_value_size: Option<usize>,
+    #[arg(long, global = true, value_name = "SECONDS", value_parser = parse_timeout, help = "Stop the script after this much wall-clock time")]
+    timeout: Option<Duration>,
+    #[arg(long, global = true, help = "Seed of `random`, for reproducible runs")]
+    seed: Option<u64>,
+    #[arg(long, global = true, help = "Let natives read files")]
+    allow_read: bool,
+    #[arg(long, global = true, help = "Let natives write files")]
+    allow_write: bool,
+    #[arg(long, global = true, help = "Let natives read environment variables")]
+    allow_env: bool,
+    #[arg(long, global = true, help = "Grant every permission")]
+    allow_all: bool,
+    #[arg(long, global = true, help = "Forbid reading stdin with `input`")]
+    deny_input: bool,
+    #[arg(long, global = true, help = "Forbid writing stdout with `print`")]
+    deny_output: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
     
     #[arg(help = "Path to the source file")]
     file_path: Option<String>, 
@@ -27,6 +72,55 @@ struct Args {
     extra: Vec<String>,
 }
 
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Rewrite the keywords of a source file in another language of the lexicon
+    Translate {
+        #[arg(long, help = "Target language, e.g. EN or UA")]
+        to: String,
+        #[arg(short, long, help = "Write the result to this file instead of stdout")]
+        output: Option<String>,
+        #[arg(help = "Path to the source file")]
+        file: String,
+    },
+    /// Print the tokens of a source file with the lexicon row each one matched
+    Tokens {
+        #[arg(long, help = "Print JSON instead of a table")]
+        json: bool,
+        #[arg(long, help = "Include whitespace and comment tokens")]
+        all: bool,
+        #[arg(help = "Path to the source file")]
+        file: String,
+    },
+    /// Print the syntax tree of a source file
+    Ast {
+        #[arg(long, help = "Print JSON instead of the debug tree")]
+        json: bool,
+        #[arg(long, requires = "output", conflicts_with = "json", help = "Write the compact binary form")]
+        binary: bool,
+        #[arg(short, long, help = "Write the result to this file instead of stdout")]
+        output: Option<String>,
+        #[arg(help = "Path to the source file")]
+        file: String,
+    },
+    /// Run a pre-parsed AST (JSON or binary) without the lexicon database
+    Exec {
+        #[arg(long, help = "Skip static type checking before execution")]
+        no_check: bool,
+        #[arg(help = "Path to the AST file written by `ast --json` or `ast --binary`")]
+        file: String,
+    },
+    /// Rewrite a source file in canonical form
+    Fmt {
+        #[arg(long, help = "Language of the keywords in the output, defaults to --lang")]
+        to: Option<String>,
+        #[arg(long, help = "Only report whether the file is formatted, exit with 1 if not")]
+        check: bool,
+        #[arg(help = "Path to the source file")]
+        file: String,
+    },
+}
+
 #[tokio::main]
 async fn main() {
     dotenv().ok();
@@ -35,7 +129,8 @@ async fn main() {
         std::process::exit(0);
     }).expect("Error setting Ctrl-C handler");
 
-    let args = Args::parse();
+    let args = Args::try_parse().unwrap_or_else(|e| usage_error(e));
+    reject_run_args_with_subcommand(&args);
 
     if !args.no_logging {
         logging::init_logging();
@@ -44,6 +139,7 @@ async fn main() {
     info!("Application started.");
 
     let config = CONFIG.clone();
+    let permissions = permissions(&args);
     let mut _current_lang = config.lang;
     let mut _config_path = config.path_config;
 
@@ -57,20 +153,302 @@ async fn main() {
     info!("Selected language: {}", _current_lang);
     info!("Config path: {}", _config_path);
 
+    let options = RunOptions {
+        no_check: args.no_check,
+        vm: args.vm,
+        limits: Limits {
+            max_call_depth: args.max_depth,
+            max_steps: args.max_steps,
+            max_value_size: args.max_value_size,
+            timeout: args.timeout,
+        },
+        permissions,
+        seed: args.seed,
+    };
+
+    match args.command {
+        Some(Command::Translate { to, output, file }) => translate_file(connect(), &to, &file, output.as_deref()),
+        Some(Command::Tokens { json, all, file }) => dump_tokens(connect(), &file, json, all),
+        Some(Command::Ast { json, binary, output, file }) => dump_ast(connect(), &_current_lang, &file, json, binary, output.as_deref()),
+        Some(Command::Exec { no_check, file }) => exec_file(&file, RunOptions { no_check, ..options }),
+        Some(Command::Fmt { to, check, file }) => format_file(connect(), to.as_deref().unwrap_or(&_current_lang), &file, check),
+        None => run_file(connect(), &_current_lang, args.file_path, args.no_cache, options),
+    }
+}
+
+/// Prints a command line error and exits with `EXIT_USAGE_ERROR` (clap's own
+/// code would be taken for `EXIT_TYPE_ERROR`); `--help` and `--version` exit 0.
+fn usage_error(e: clap::Error) -> ! {
+    let code = if e.use_stderr() { EXIT_USAGE_ERROR } else { 0 };
+    let _ = e.print();
+    std::process::exit(code);
+}
+
+/// Seconds as a `Duration`; negative, NaN and overflowing values are rejected.
+fn parse_timeout(value: &str) -> Result<Duration, String> {
+    let seconds: f64 = value.parse().map_err(|e| format!("{}", e))?;
+    Duration::try_from_secs_f64(seconds).map_err(|_| format!("'{}' is not a valid number of seconds", value))
+}
+
+/// Options of running a source file are not read by the subcommands.
+///
+/// `args_conflicts_with_subcommands` would reject them too, but it also stops
+/// clap from recognizing a subcommand after a global option
+/// (`--no-logging translate ...`), so the conflict is checked here.
+fn reject_run_args_with_subcommand(args: &Args) {
+    if args.command.is_none() {
+        return;
+    }
+    let run_args = [
+        ("--no-check", args.no_check),
+        ("--no-cache", args.no_cache),
+        ("[FILE_PATH]", args.file_path.is_some() || !args.extra.is_empty()),
+    ];
+    if let Some((name, _)) = run_args.iter().find(|(_, used)| *used) {
+        usage_error(Args::command().error(
+            ErrorKind::ArgumentConflict,
+            format!("the argument '{}' cannot be used with a subcommand", name),
+        ));
+    }
+}
+
+/// How a script is checked and run.
+struct RunOptions {
+    no_check: bool,
+    vm: bool,
+    limits: Limits,
+    permissions: Permissions,
+    /// Seed of `random`; taken from the clock when not set.
+    seed: Option<u64>,
+}
+
+fn permissions(args: &Args) -> Permissions {
+    let mut permissions = if args.allow_all { Permissions::all() } else { Permissions::default() };
+    let flags = [
+        (args.allow_read, Permission::Read),
+        (args.allow_write, Permission::Write),
+        (args.allow_env, Permission::Env),
+    ];
+    for (allowed, permission) in flags {
+        if allowed {
+            permissions = permissions.allow(permission);
+        }
+    }
+    if args.deny_input {
+        permissions = permissions.deny(Permission::Input);
+    }
+    if args.deny_output {
+        permissions = permissions.deny(Permission::Output);
+    }
+    permissions
+}
+
+fn connect() -> DB {
     let db_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
-    let _db = DB::new(&db_url);
-    
-    let mut lexer = tokenizer::Parser::new(_db);
+    DB::new(&db_url)
+}
+
+fn read_source(path: &str) -> String {
+    info!("Reading source code from file: {}", path);
+    fs::read_to_string(path).expect("Failed to read source file")
+}
+
+fn translator_for(db: &DB, target_lang: &str) -> Translator {
+    let glossary = Glossary::new(&db.select_glossary());
+    let translator = Translator::new(target_lang, &db.select_lexems_by_lang(target_lang), glossary);
+    if !translator.has_lexems() {
+        eprintln!("Language '{}' has no lexemes in the lexicon.", target_lang);
+        std::process::exit(EXIT_USAGE_ERROR);
+    }
+    translator
+}
+
+fn translate_file(db: DB, target_lang: &str, path: &str, output: Option<&str>) {
+    let input_code = read_source(path);
+    let translator = translator_for(&db, target_lang);
+
+    let tokens = tokenizer::Parser::new(db).parse_lossless(&input_code);
+    let translated = translator.translate(&tokens);
+
+    match output {
+        Some(out_path) => {
+            fs::write(out_path, translated).expect("Failed to write translated file");
+            info!("Translation written to {}", out_path);
+        },
+        None => print!("{}", translated),
+    }
+}
+
+fn dump_tokens(db: DB, path: &str, json: bool, all: bool) {
+    let input_code = read_source(path);
+    let mut lexer = tokenizer::Parser::new(db);
+    let tokens = if all { lexer.parse_lossless(&input_code) } else { lexer.parse(&input_code) };
 
-    let input_code = if let Some(path) = args.file_path {
-        info!("Reading source code from file: {}", path);
-        fs::read_to_string(&path).expect("Failed to read source file")
+    if json {
+        println!("{}", serde_json::to_string_pretty(&tokens).expect("Failed to serialize tokens"));
+        return;
+    }
+    println!("{:<8} {:<20} {:<12} {:>5}  {:<20} LANG", "POS", "VALUE", "TYPE", "ID", "LEXEM");
+    for token in &tokens {
+        println!(
+            "{:<8} {:<20} {:<12} {:>5}  {:<20} {}",
+            token.span.to_string(),
+            format!("{:?}", token.value),
+            token.token_type,
+            token.std_token_id,
+            format!("{:?}", token.lexem),
+            token.lang.as_deref().unwrap_or("-"),
+        );
+    }
+}
+
+/// `--json` and `--binary` also write the lexicon rows of `lang`, which
+/// `exec` runs the program with.
+fn dump_ast(db: DB, lang: &str, path: &str, json: bool, binary: bool, output: Option<&str>) {
+    let input_code = read_source(path);
+    let glossary = db.select_glossary();
+    let lexems = db.select_lexems_by_lang(lang);
+    let tokens = tokenizer::Parser::new(db).parse(&input_code);
+    let program = ParsedProgram { statements: parse_or_exit(tokens), glossary, lexems };
+
+    let bytes = if binary {
+        serialize::to_binary(&program)
+    } else if json {
+        serialize::to_json(&program).into_bytes()
     } else {
+        format!("{:#?}", program.statements).into_bytes()
+    };
+
+    match output {
+        Some(out_path) => {
+            fs::write(out_path, bytes).expect("Failed to write AST file");
+            info!("AST written to {}", out_path);
+        },
+        None => println!("{}", String::from_utf8_lossy(&bytes)),
+    }
+}
+
+fn parse_or_exit(tokens: Vec<Token>) -> Vec<Stmt> {
+    AstParser::new(tokens).parse().unwrap_or_else(|e| syntax_error(e))
+}
+
+fn syntax_error(e: ParseError) -> ! {
+    eprintln!("{}", e);
+    std::process::exit(EXIT_SYNTAX_ERROR);
+}
+
+fn exec_file(path: &str, options: RunOptions) {
+    info!("Reading AST from file: {}", path);
+    let bytes = fs::read(path).expect("Failed to read AST file");
+    let program = match serialize::from_bytes(&bytes) {
+        Ok(program) => program,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(EXIT_USAGE_ERROR);
+        }
+    };
+    execute_program(program, options);
+}
+
+fn format_file(db: DB, target_lang: &str, path: &str, check: bool) {
+    let input_code = read_source(path);
+    let translator = translator_for(&db, target_lang);
+
+    let tokens = tokenizer::Parser::new(db).parse_lossless(&input_code);
+    let formatted = format::format(&tokens, &translator).unwrap_or_else(|e| syntax_error(e));
+
+    if formatted == input_code {
+        info!("{} is already formatted", path);
+    } else if check {
+        eprintln!("{} is not formatted", path);
+        std::process::exit(EXIT_NOT_FORMATTED);
+    } else {
+        fs::write(path, formatted).expect("Failed to write formatted file");
+        info!("Formatted {}", path);
+    }
+}
+
+fn run_file(db: DB, lang: &str, file_path: Option<String>, no_cache: bool, options: RunOptions) {
+    let Some(path) = file_path else {
         error!("No file provided, running default test code.");
         return;
     };
+    let input_code = read_source(&path);
+    let path = std::path::Path::new(&path);
+
+    // Лексикон читається з БД лише коли кеш застарів
+    let program_cache = ProgramCache::new(configuration::data_dir().join("cache"));
+    let lexicon = db.select_lexicon_revision().unwrap_or_else(|| cache::lexicon_fingerprint(&db.select_lexicon(), &db.select_glossary()));
+    let cached = if no_cache { None } else { program_cache.load(path, &input_code, lang, lexicon) };
+
+    let program = match cached {
+        Some(program) => program,
+        None => {
+            let glossary = db.select_glossary();
+            let lexems = db.select_lexems_by_lang(lang);
+            let statements = parse_or_exit(tokenizer::Parser::new(db).parse(&input_code));
+            let program = ParsedProgram { statements, glossary, lexems };
+            program_cache.store(path, &input_code, lang, lexicon, &program);
+            program
+        }
+    };
+    execute_program(program, options);
+}
+
+/// Runs `program` with the glossary, type names and date names of its lexicon rows.
+fn execute_program(program: ParsedProgram, options: RunOptions) {
+    let mut type_names = HashMap::new();
+    for row in program.lexems.iter().filter(|row| row.type_info.is_some()) {
+        if let Some(data_type) = DataType::from_std_id(row.std_lexem) {
+            type_names.insert(data_type, row.lexem.clone());
+        }
+    }
+    let lexems = &program.lexems;
+    let date_names = DateNames::localized(|id| lexems.iter().find(|row| row.std_lexem == id).map(|row| row.lexem.clone()));
+    execute(Glossary::new(&program.glossary), type_names, date_names, program.statements, options);
+}
+
+fn execute(
+    glossary: Glossary,
+    type_names: HashMap<DataType, String>,
+    date_names: DateNames,
+    statements: Vec<Stmt>,
+    options: RunOptions,
+) {
+    if !options.no_check && let Err(errors) = TypeChecker::with_glossary(glossary.clone()).check(&statements) {
+        for e in &errors {
+            error!("{}", e);
+            eprintln!("{}", e);
+        }
+        eprintln!("{} type error(s) found, not running.", errors.len());
+        std::process::exit(EXIT_TYPE_ERROR);
+    }
+
+    let result = if options.vm {
+        let mut vm = Vm::with_glossary(glossary);
+        vm.type_names = type_names;
+        vm.date_names = date_names;
+        vm.limits = options.limits;
+        vm.permissions = options.permissions;
+        if let Some(seed) = options.seed {
+            vm.natives = Natives::with_seed(seed);
+        }
+        vm.interpret(statements)
+    } else {
+        let mut interp = Interpreter::with_glossary(glossary);
+        interp.type_names = type_names;
+        interp.date_names = date_names;
+        interp.limits = options.limits;
+        interp.permissions = options.permissions;
+        if let Some(seed) = options.seed {
+            interp.natives = Natives::with_seed(seed);
+        }
+        interp.interpret(statements)
+    };
 
-    let statements = AstParser::new(lexer.parse(&input_code)).parse();
-    let mut interp = Interpreter::new();
-    interp.interpret(statements);
+    if let Err(e) = result {
+        error!("{}", e);
+        eprintln!("{}", e);
+        std::process::exit(if e.limit.is_some() { EXIT_LIMIT_EXCEEDED } else { EXIT_RUNTIME_ERROR });
+    }
 }
\ No newline at end of file
@@ -23,9 +23,30 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    IdentifierGlossaryTBL (id) {
+        id -> Unsigned<Integer>,
+        #[max_length = 64]
+        lang_name -> Varchar,
+        #[max_length = 128]
+        identifier -> Varchar,
+        #[max_length = 128]
+        canonical -> Varchar,
+    }
+}
+
+diesel::table! {
+    LexiconRevisionTBL (id) {
+        id -> Unsigned<TinyInt>,
+        revision -> Unsigned<BigInt>,
+    }
+}
+
 diesel::joinable!(AllLexemsTBL -> StdLexemeTBL (std_lexem));
 
 diesel::allow_tables_to_appear_in_same_query!(
     AllLexemsTBL,
+    IdentifierGlossaryTBL,
+    LexiconRevisionTBL,
     StdLexemeTBL,
 );